@@ -8,9 +8,70 @@ codegen_gcc_invalid_minimum_alignment =
 codegen_gcc_lto_not_supported =
     LTO is not supported. You may get a linker error.
 
+codegen_gcc_unsupported_sanitizer =
+    the `{$sanitizer}` sanitizer is not supported by the GCC backend
+
+codegen_gcc_no_sanitize_unsupported =
+    `#[no_sanitize]` is not supported by the GCC backend for the sanitizers: {$sanitizers}
+    .note = libgccjit does not support disabling the instrumentation for a single function
+
 codegen_gcc_tied_target_features = the target features {$features} must all be either enabled or disabled together
     .help = add the missing features in a `target_feature` attribute
 
+codegen_gcc_empty_link_section = link section name cannot be empty
+
+codegen_gcc_invalid_mach_o_link_section = invalid Mach-O link section `{$section}`
+    .help = Mach-O link sections must be of the form `segment,section`, where both names are at most 16 characters long
+
+codegen_gcc_function_link_section_ignored =
+    `#[link_section]` is ignored on functions for this target
+    .note = libgccjit does not support setting the section of a function, so this is only done for ELF object files
+
+codegen_gcc_used_linker_unsupported =
+    `#[used(linker)]` cannot prevent the linker from removing this static
+    .note = the assembler does not support the SHF_GNU_RETAIN section flag (binutils 2.36 or newer is required)
+
+codegen_gcc_function_link_section_not_found =
+    `#[link_section]` is ignored on the function `{$symbol}`
+    .note = the section of this function was not found in the object file `{$path}`
+
+codegen_gcc_set_function_link_sections = failed to set the link sections of the functions in `{$path}`: {$err}
+
+codegen_gcc_naked_function_unsupported_target =
+    GCC backend only supports naked functions on ELF targets
+
+codegen_gcc_naked_function_unsupported_linkage =
+    GCC backend does not support the linkage `{$linkage}` for naked functions
+
+codegen_gcc_unsupported_linkage =
+    GCC backend does not support the linkage `{$linkage}`
+
+codegen_gcc_unsupported_calling_convention =
+    the calling convention `{$conv}` is not supported by the GCC backend
+    .note = it requires the GCC attribute `{$attribute}`, which libgccjit cannot set yet
+
+codegen_gcc_c_variadic_definition_unsupported =
+    GCC backend does not support defining C-variadic functions
+    .note = libgccjit does not support `va_start` yet
+
+codegen_gcc_va_arg_unsupported =
+    GCC backend does not support `va_arg`
+    .note = libgccjit does not support `va_arg` yet
+
+codegen_gcc_unsupported_inline_asm_register =
+    the register `{$register}` is not supported in inline asm by the GCC backend on `{$arch}`
+
+codegen_gcc_non_constant_amx_tile =
+    the tile arguments of the LLVM intrinsic `{$name}` must be constants
+    .note = the tile numbers are encoded in the instructions
+
+codegen_gcc_unsupported_llvm_intrinsic =
+    the LLVM intrinsic `{$name}` is not supported by the GCC backend on `{$arch}`
+    .note = libgccjit has no equivalent builtin for this intrinsic yet
+
+codegen_gcc_unsupported_simd_intrinsic =
+    the SIMD intrinsic `{$name}` is not supported by the GCC backend on `{$arch}`
+
 codegen_gcc_unwinding_inline_asm =
     GCC backend does not support unwinding from inline asm
 
@@ -1,17 +1,22 @@
 #[cfg(feature = "master")]
 use gccjit::FnAttribute;
 use gccjit::{ToLValue, ToRValue, Type};
-use rustc_codegen_ssa::traits::{AbiBuilderMethods, BaseTypeMethods};
-use rustc_data_structures::fx::FxHashSet;
+use rustc_codegen_ssa::traits::{AbiBuilderMethods, BaseTypeMethods, BuilderMethods};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_middle::bug;
-use rustc_middle::ty::layout::LayoutOf;
+use rustc_middle::ty::layout::{LayoutOf, TyAndLayout};
 use rustc_middle::ty::Ty;
 #[cfg(feature = "master")]
 use rustc_session::config;
-use rustc_target::abi::call::{ArgAttributes, CastTarget, FnAbi, PassMode, Reg, RegKind};
+use rustc_session::Session;
+use rustc_span::def_id::DefId;
+use rustc_target::abi::call::{
+    ArgAttributes, CastTarget, Conv, FnAbi, PassMode, Reg, RegKind, RiscvInterruptKind,
+};
 
 use crate::builder::Builder;
 use crate::context::CodegenCx;
+use crate::errors::UnsupportedCallingConvention;
 use crate::intrinsic::ArgAbiExt;
 use crate::type_of::LayoutGccExt;
 
@@ -26,7 +31,43 @@ impl<'a, 'gcc, 'tcx> AbiBuilderMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
         } else {
             false
         };
-        if on_stack { param.to_lvalue().get_address(None) } else { param.to_rvalue() }
+        if on_stack {
+            return param.to_lvalue().get_address(None);
+        }
+        let float_type = self
+            .soft_float_function_params
+            .borrow()
+            .get(&func)
+            .and_then(|float_types| float_types.get(&index).copied());
+        match float_type {
+            Some(float_type) => self.bitcast(param.to_rvalue(), float_type),
+            None => param.to_rvalue(),
+        }
+    }
+}
+
+impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
+    /// With soft-float, the floating-point values are passed in general purpose registers, so
+    /// they are passed as integers of the same size as far as GCC is concerned.
+    /// The floating-point operations themselves are lowered to library calls by GCC.
+    pub fn soft_float_abi_type(&self, typ: Type<'gcc>) -> Type<'gcc> {
+        if self.soft_float && self.is_float_type(typ) {
+            self.type_ix(typ.get_size() as u64 * 8)
+        } else {
+            typ
+        }
+    }
+
+    /// With soft-float, the pairs containing floating-point values are returned as a struct of
+    /// integers of the same sizes for the same reason.
+    pub fn soft_float_pair_abi_type(&self, layout: TyAndLayout<'tcx>) -> Type<'gcc> {
+        if !self.soft_float {
+            return layout.immediate_gcc_type(self);
+        }
+        let element_types = [0, 1].map(|index| {
+            self.soft_float_abi_type(layout.scalar_pair_element_gcc_type(self, index))
+        });
+        self.type_struct(&element_types, false)
     }
 }
 
@@ -81,12 +122,70 @@ impl GccType for Reg {
     fn gcc_type<'gcc>(&self, cx: &CodegenCx<'gcc, '_>) -> Type<'gcc> {
         match self.kind {
             RegKind::Integer => cx.type_ix(self.size.bits()),
+            RegKind::Float if cx.soft_float => cx.type_ix(self.size.bits()),
             RegKind::Float => match self.size.bits() {
                 32 => cx.type_f32(),
                 64 => cx.type_f64(),
                 _ => bug!("unsupported float: {:?}", self),
             },
-            RegKind::Vector => unimplemented!(), //cx.type_vector(cx.type_i8(), self.size.bytes()),
+            // NOTE: GCC passes all the vector types of the same size in the same registers, so
+            // the element type does not matter.
+            RegKind::Vector => cx.type_vector(cx.type_i8(), self.size.bytes()),
+        }
+    }
+}
+
+/// Get the GCC attribute implementing the calling convention of the function, or `None` if the
+/// default calling convention of the target can be used for this signature.
+pub fn conv_to_gcc_attribute<'tcx>(
+    fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+    sess: &Session,
+) -> Option<&'static str> {
+    let target = &sess.target;
+    match fn_abi.conv {
+        Conv::C | Conv::Rust => None,
+        // NOTE: those only change which registers are callee-saved. The callers and the callees
+        // are both compiled by this backend, so using the default calling convention is fine.
+        Conv::Cold | Conv::PreserveMost | Conv::PreserveAll => None,
+        // NOTE: the base AAPCS only differs from the default calling convention of the hard-float
+        // targets (AAPCS-VFP) for the floating-point values passed in registers: the aggregates
+        // were already classified for the base AAPCS by rustc. It is used by compiler_builtins
+        // for the `__aeabi_*` functions, which the code compiled by GCC never calls on those
+        // targets, so it is accepted to be able to build the sysroot.
+        // TODO: pass the floating-point values as integers, like with soft-float, for this
+        // calling convention on the hard-float targets.
+        Conv::ArmAapcs => None,
+        Conv::CCmseNonSecureCall => Some("cmse_nonsecure_call"),
+        Conv::Msp430Intr => Some("interrupt"),
+        Conv::PtxKernel => Some("kernel"),
+        Conv::X86Fastcall => Some("fastcall"),
+        Conv::X86Intr => Some("interrupt"),
+        Conv::X86Stdcall => Some("stdcall"),
+        Conv::X86ThisCall => Some("thiscall"),
+        Conv::X86VectorCall => Some("vectorcall"),
+        Conv::X86_64SysV => target.is_like_windows.then_some("sysv_abi"),
+        Conv::X86_64Win64 => (!target.is_like_windows).then_some("ms_abi"),
+        Conv::AvrInterrupt => Some("interrupt"),
+        Conv::AvrNonBlockingInterrupt => Some("signal"),
+        Conv::RiscvInterrupt { kind } => match kind {
+            RiscvInterruptKind::Machine => Some("interrupt(\"machine\")"),
+            RiscvInterruptKind::Supervisor => Some("interrupt(\"supervisor\")"),
+        },
+    }
+}
+
+impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
+    /// Report an error if the function uses a calling convention that is not supported. This is
+    /// done once for each definition and declaration, where the span of the item is known, instead
+    /// of for each use of the function type.
+    pub fn check_calling_convention(&self, fn_abi: &FnAbi<'tcx, Ty<'tcx>>, def_id: DefId) {
+        if let Some(attribute) = conv_to_gcc_attribute(fn_abi, self.sess()) {
+            // TODO: apply the attribute when libgccjit supports the calling convention attributes.
+            self.tcx.dcx().emit_err(UnsupportedCallingConvention {
+                span: self.tcx.def_span(def_id),
+                conv: format!("{:?}", fn_abi.conv),
+                attribute,
+            });
         }
     }
 }
@@ -96,6 +195,8 @@ pub struct FnAbiGcc<'gcc> {
     pub arguments_type: Vec<Type<'gcc>>,
     pub is_c_variadic: bool,
     pub on_stack_param_indices: FxHashSet<usize>,
+    /// Floating-point types of the parameters passed as integers because of soft-float.
+    pub soft_float_param_types: FxHashMap<usize, Type<'gcc>>,
     #[cfg(feature = "master")]
     pub fn_attributes: Vec<FnAttribute<'gcc>>,
 }
@@ -109,21 +210,20 @@ pub trait FnAbiGccExt<'gcc, 'tcx> {
 impl<'gcc, 'tcx> FnAbiGccExt<'gcc, 'tcx> for FnAbi<'tcx, Ty<'tcx>> {
     fn gcc_type(&self, cx: &CodegenCx<'gcc, 'tcx>) -> FnAbiGcc<'gcc> {
         let mut on_stack_param_indices = FxHashSet::default();
+        let mut soft_float_param_types = FxHashMap::default();
+        let mut soft_float_abi_type = |ty: Type<'gcc>, arg_index: usize| {
+            let abi_ty = cx.soft_float_abi_type(ty);
+            if abi_ty != ty {
+                soft_float_param_types.insert(arg_index, ty);
+            }
+            abi_ty
+        };
 
         // This capacity calculation is approximate.
         let mut argument_tys = Vec::with_capacity(
             self.args.len() + if let PassMode::Indirect { .. } = self.ret.mode { 1 } else { 0 },
         );
 
-        let return_type = match self.ret.mode {
-            PassMode::Ignore => cx.type_void(),
-            PassMode::Direct(_) | PassMode::Pair(..) => self.ret.layout.immediate_gcc_type(cx),
-            PassMode::Cast { ref cast, .. } => cast.gcc_type(cx),
-            PassMode::Indirect { .. } => {
-                argument_tys.push(cx.type_ptr_to(self.ret.memory_ty(cx)));
-                cx.type_void()
-            }
-        };
         #[cfg(feature = "master")]
         let mut non_null_args = Vec::new();
 
@@ -138,26 +238,46 @@ impl<'gcc, 'tcx> FnAbiGccExt<'gcc, 'tcx> for FnAbi<'tcx, Ty<'tcx>> {
             if attrs.regular.contains(rustc_target::abi::call::ArgAttribute::NonNull) {
                 non_null_args.push(arg_index as i32 + 1);
             }
+            // TODO: use the `access` attribute for `ReadOnly` and the dereferenceable size
+            // (`attrs.pointee_size`) when libgccjit supports it.
+            // NOTE: making the pointee `const` for `ReadOnly` would not help GCC since the
+            // constness can be casted away in C.
             ty
         };
         #[cfg(not(feature = "master"))]
         let apply_attrs = |ty: Type<'gcc>, _attrs: &ArgAttributes, _arg_index: usize| ty;
 
+        // TODO: apply the attributes of the return value (`returns_nonnull` and `malloc`) when
+        // libgccjit supports them.
+        let return_type = match self.ret.mode {
+            PassMode::Ignore => cx.type_void(),
+            PassMode::Direct(_) => cx.soft_float_abi_type(self.ret.layout.immediate_gcc_type(cx)),
+            PassMode::Pair(..) => cx.soft_float_pair_abi_type(self.ret.layout),
+            PassMode::Cast { ref cast, .. } => cast.gcc_type(cx),
+            PassMode::Indirect { attrs, .. } => {
+                // NOTE: the return place pointer is `noalias` and `nonnull`, so it gets the
+                // `restrict` qualifier and the `nonnull` attribute.
+                let ty = cx.type_ptr_to(self.ret.memory_ty(cx));
+                argument_tys.push(apply_attrs(ty, &attrs, argument_tys.len()));
+                cx.type_void()
+            }
+        };
+
         for arg in self.args.iter() {
             let arg_ty = match arg.mode {
                 PassMode::Ignore => continue,
                 PassMode::Pair(a, b) => {
                     let arg_pos = argument_tys.len();
-                    argument_tys.push(apply_attrs(
+                    let typ1 = soft_float_abi_type(
                         arg.layout.scalar_pair_element_gcc_type(cx, 0),
-                        &a,
                         arg_pos,
-                    ));
-                    argument_tys.push(apply_attrs(
+                    );
+                    argument_tys.push(apply_attrs(typ1, &a, arg_pos));
+                    let typ2 = soft_float_abi_type(
                         arg.layout.scalar_pair_element_gcc_type(cx, 1),
-                        &b,
                         arg_pos + 1,
-                    ));
+                    );
+                    argument_tys.push(apply_attrs(typ2, &b, arg_pos + 1));
                     continue;
                 }
                 PassMode::Cast { ref cast, pad_i32 } => {
@@ -174,7 +294,9 @@ impl<'gcc, 'tcx> FnAbiGccExt<'gcc, 'tcx> for FnAbi<'tcx, Ty<'tcx>> {
                     arg.memory_ty(cx)
                 }
                 PassMode::Direct(attrs) => {
-                    apply_attrs(arg.layout.immediate_gcc_type(cx), &attrs, argument_tys.len())
+                    let arg_pos = argument_tys.len();
+                    let ty = soft_float_abi_type(arg.layout.immediate_gcc_type(cx), arg_pos);
+                    apply_attrs(ty, &attrs, arg_pos)
                 }
                 PassMode::Indirect { attrs, meta_attrs: None, on_stack: false } => {
                     apply_attrs(cx.type_ptr_to(arg.memory_ty(cx)), &attrs, argument_tys.len())
@@ -209,13 +331,16 @@ impl<'gcc, 'tcx> FnAbiGccExt<'gcc, 'tcx> for FnAbi<'tcx, Ty<'tcx>> {
             arguments_type: argument_tys,
             is_c_variadic: self.c_variadic,
             on_stack_param_indices,
+            soft_float_param_types,
             #[cfg(feature = "master")]
             fn_attributes: fn_attrs,
         }
     }
 
     fn ptr_to_gcc_type(&self, cx: &CodegenCx<'gcc, 'tcx>) -> Type<'gcc> {
-        // FIXME(antoyo): Should we do something with `FnAbiGcc::fn_attributes`?
+        // NOTE: GCC does not support attributes on function pointer types, so
+        // `FnAbiGcc::fn_attributes` is ignored here, but the `restrict` qualifiers are kept in
+        // the parameter types.
         let FnAbiGcc { return_type, arguments_type, is_c_variadic, on_stack_param_indices, .. } =
             self.gcc_type(cx);
         let pointer_type =
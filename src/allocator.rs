@@ -108,9 +108,8 @@ fn create_wrapper_function(
         #[cfg(feature = "master")]
         func.add_attribute(FnAttribute::Visibility(gccjit::Visibility::Hidden));
     }
-    if tcx.sess.must_emit_unwind_tables() {
-        // TODO(antoyo): emit unwind tables.
-    }
+    // NOTE: the unwind tables are enabled for the whole context in new_context() when
+    // tcx.sess.must_emit_unwind_tables() is true, so they are emitted for this function as well.
 
     let args: Vec<_> = types
         .iter()
@@ -2,21 +2,30 @@ use std::borrow::Cow;
 
 use gccjit::{LValue, RValue, ToRValue, Type};
 use rustc_ast::ast::{InlineAsmOptions, InlineAsmTemplatePiece};
+use rustc_attr::InstructionSetAttr;
+use rustc_codegen_ssa::common::asm_const_to_str;
 use rustc_codegen_ssa::mir::operand::OperandValue;
 use rustc_codegen_ssa::mir::place::PlaceRef;
 use rustc_codegen_ssa::traits::{
     AsmBuilderMethods, AsmMethods, BaseTypeMethods, BuilderMethods, GlobalAsmOperandRef,
     InlineAsmOperandRef,
 };
-use rustc_middle::bug;
-use rustc_middle::ty::Instance;
+use rustc_middle::mir::mono::{Linkage, MonoItemData, Visibility};
+use rustc_middle::mir::{InlineAsmOperand, TerminatorKind};
+use rustc_middle::ty::layout::LayoutOf;
+use rustc_middle::ty::{self, Instance};
+use rustc_middle::{bug, span_bug};
+use rustc_span::symbol::sym;
 use rustc_span::Span;
 use rustc_target::asm::*;
 
 use crate::builder::Builder;
 use crate::callee::get_fn;
 use crate::context::CodegenCx;
-use crate::errors::UnwindingInlineAsm;
+use crate::errors::{
+    NakedFunctionUnsupportedLinkage, NakedFunctionUnsupportedTarget, UnsupportedInlineAsmRegister,
+    UnwindingInlineAsm,
+};
 use crate::type_of::LayoutGccExt;
 
 // Rust asm! and GCC Extended Asm semantics differ substantially.
@@ -122,6 +131,32 @@ impl<'a, 'gcc, 'tcx> AsmBuilderMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
             return;
         }
 
+        // NOTE: the explicit registers, including the ones added by `clobber_abi`, are checked
+        // before generating anything since the passes below expect GCC to know all of them.
+        let mut unsupported_registers = vec![];
+        for op in rust_operands {
+            let reg = match *op {
+                InlineAsmOperandRef::Out { reg, .. }
+                | InlineAsmOperandRef::In { reg, .. }
+                | InlineAsmOperandRef::InOut { reg, .. } => reg,
+                _ => continue,
+            };
+            if let InlineAsmRegOrRegClass::Reg(reg) = reg
+                && reg_name_to_gcc(reg).is_none()
+                && !unsupported_registers.contains(&reg)
+            {
+                unsupported_registers.push(reg);
+                self.sess().dcx().emit_err(UnsupportedInlineAsmRegister {
+                    span: span[0],
+                    register: reg.name(),
+                    arch: &self.tcx.sess.target.arch,
+                });
+            }
+        }
+        if !unsupported_registers.is_empty() {
+            return;
+        }
+
         let asm_arch = self.tcx.sess.asm_arch.unwrap();
         let is_x86 = matches!(asm_arch, InlineAsmArch::X86 | InlineAsmArch::X86_64);
         let att_dialect = is_x86 && options.contains(InlineAsmOptions::ATT_SYNTAX);
@@ -313,7 +348,7 @@ impl<'a, 'gcc, 'tcx> AsmBuilderMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
                         tmp_var.set_register_name(reg_name);
 
                         outputs.push(AsmOutOperand {
-                            constraint: "r",
+                            constraint: reg_class_to_gcc(reg.reg_class()),
                             rust_idx,
                             late,
                             readwrite: false,
@@ -334,7 +369,7 @@ impl<'a, 'gcc, 'tcx> AsmBuilderMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
                         self.llbb().add_assignment(None, reg_var, value.immediate());
 
                         inputs.push(AsmInOperand {
-                            constraint: "r".into(),
+                            constraint: reg_class_to_gcc(reg.reg_class()).into(),
                             rust_idx,
                             val: reg_var.to_rvalue(),
                         });
@@ -352,7 +387,7 @@ impl<'a, 'gcc, 'tcx> AsmBuilderMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
                         tmp_var.set_register_name(reg_name);
 
                         outputs.push(AsmOutOperand {
-                            constraint: "r",
+                            constraint: reg_class_to_gcc(reg.reg_class()),
                             rust_idx,
                             late,
                             readwrite: false,
@@ -588,105 +623,126 @@ fn estimate_template_length(
     res
 }
 
-/// Converts a register class to a GCC constraint code.
+/// Converts a register class to a GCC constraint code, or an explicit register to its GCC name.
 fn reg_to_gcc(reg: InlineAsmRegOrRegClass) -> ConstraintOrRegister {
-    let constraint = match reg {
-        // For vector registers LLVM wants the register name to match the type size.
-        InlineAsmRegOrRegClass::Reg(reg) => {
-            match reg {
-                InlineAsmReg::X86(_) => {
-                    // TODO(antoyo): add support for vector register.
-                    //
-                    // // For explicit registers, we have to create a register variable: https://stackoverflow.com/a/31774784/389119
-                    return ConstraintOrRegister::Register(match reg.name() {
-                        // Some of registers' names does not map 1-1 from rust to gcc
-                        "st(0)" => "st",
-
-                        name => name,
-                    });
-                }
+    match reg {
+        // For explicit registers, we have to create a register variable: https://stackoverflow.com/a/31774784/389119
+        InlineAsmRegOrRegClass::Reg(reg) => match reg_name_to_gcc(reg) {
+            Some(name) => ConstraintOrRegister::Register(name),
+            None => bug!("unsupported register {} should have been reported", reg.name()),
+        },
+        InlineAsmRegOrRegClass::RegClass(reg_class) => {
+            ConstraintOrRegister::Constraint(reg_class_to_gcc(reg_class))
+        }
+    }
+}
 
-                _ => unimplemented!(),
-            }
+/// Converts an explicit register to the name GCC uses for it in register variables and clobbers.
+/// Returns `None` for the registers of the architectures whose names are not known to match.
+fn reg_name_to_gcc(reg: InlineAsmReg) -> Option<&'static str> {
+    match reg {
+        // Some of registers' names does not map 1-1 from rust to gcc
+        InlineAsmReg::X86(X86InlineAsmReg::st0) => Some("st"),
+        // NOTE: GCC names the floating-point registers frN instead of fN on PowerPC.
+        InlineAsmReg::PowerPC(reg) if reg.reg_class() == PowerPCInlineAsmRegClass::freg => {
+            const FLOAT_REGISTERS: [&str; 32] = [
+                "fr0", "fr1", "fr2", "fr3", "fr4", "fr5", "fr6", "fr7", "fr8", "fr9", "fr10",
+                "fr11", "fr12", "fr13", "fr14", "fr15", "fr16", "fr17", "fr18", "fr19", "fr20",
+                "fr21", "fr22", "fr23", "fr24", "fr25", "fr26", "fr27", "fr28", "fr29", "fr30",
+                "fr31",
+            ];
+            Some(FLOAT_REGISTERS[reg as usize - PowerPCInlineAsmReg::f0 as usize])
         }
-        // They can be retrieved from https://gcc.gnu.org/onlinedocs/gcc/Machine-Constraints.html
-        InlineAsmRegOrRegClass::RegClass(reg) => match reg {
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg) => "w",
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg_low16) => "x",
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => {
-                unreachable!("clobber-only")
-            }
-            InlineAsmRegClass::Arm(ArmInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low16)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low8)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg_low16)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low8)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low4)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg) => "t",
-            InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_upper) => "d",
-            InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_pair) => "r",
-            InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_iw) => "w",
-            InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_ptr) => "e",
-            InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::wreg) => "w",
-            InlineAsmRegClass::Hexagon(HexagonInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::LoongArch(LoongArchInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::LoongArch(LoongArchInlineAsmRegClass::freg) => "f",
-            InlineAsmRegClass::M68k(M68kInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::M68k(M68kInlineAsmRegClass::reg_addr) => "a",
-            InlineAsmRegClass::M68k(M68kInlineAsmRegClass::reg_data) => "d",
-            InlineAsmRegClass::CSKY(CSKYInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::CSKY(CSKYInlineAsmRegClass::freg) => "f",
-            InlineAsmRegClass::Mips(MipsInlineAsmRegClass::reg) => "d", // more specific than "r"
-            InlineAsmRegClass::Mips(MipsInlineAsmRegClass::freg) => "f",
-            InlineAsmRegClass::Msp430(Msp430InlineAsmRegClass::reg) => "r",
-            // https://github.com/gcc-mirror/gcc/blob/master/gcc/config/nvptx/nvptx.md -> look for
-            // "define_constraint".
-            InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg16) => "h",
-            InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg32) => "r",
-            InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg64) => "l",
-
-            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg_nonzero) => "b",
-            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::freg) => "f",
-            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::cr)
-            | InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::xer) => {
-                unreachable!("clobber-only")
-            }
-            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::freg) => "f",
-            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => {
-                unreachable!("clobber-only")
-            }
-            InlineAsmRegClass::X86(X86InlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_abcd) => "Q",
-            InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_byte) => "q",
-            InlineAsmRegClass::X86(X86InlineAsmRegClass::xmm_reg)
-            | InlineAsmRegClass::X86(X86InlineAsmRegClass::ymm_reg) => "x",
-            InlineAsmRegClass::X86(X86InlineAsmRegClass::zmm_reg) => "v",
-            InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg) => "Yk",
-            InlineAsmRegClass::X86(
-                X86InlineAsmRegClass::kreg0
-                | X86InlineAsmRegClass::x87_reg
-                | X86InlineAsmRegClass::mmx_reg
-                | X86InlineAsmRegClass::tmm_reg,
-            ) => unreachable!("clobber-only"),
-            InlineAsmRegClass::SpirV(SpirVInlineAsmRegClass::reg) => {
-                bug!("GCC backend does not support SPIR-V")
-            }
-            InlineAsmRegClass::Wasm(WasmInlineAsmRegClass::local) => "r",
-            InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg) => "r",
-            InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg_addr) => "a",
-            InlineAsmRegClass::S390x(S390xInlineAsmRegClass::freg) => "f",
-            InlineAsmRegClass::Err => unreachable!(),
-        },
-    };
+        // TODO: the whole condition register `cr` has no name in GCC, so clobbering it only
+        // works through the individual fields `cr0` to `cr7`.
+        InlineAsmReg::X86(_)
+        | InlineAsmReg::AArch64(_)
+        | InlineAsmReg::Arm(_)
+        | InlineAsmReg::RiscV(_)
+        | InlineAsmReg::PowerPC(_)
+        | InlineAsmReg::S390x(_)
+        | InlineAsmReg::M68k(_) => Some(reg.name()),
+        _ => None,
+    }
+}
 
-    ConstraintOrRegister::Constraint(constraint)
+/// Converts a register class to a GCC constraint code.
+fn reg_class_to_gcc(reg_class: InlineAsmRegClass) -> &'static str {
+    match reg_class {
+        // They can be retrieved from https://gcc.gnu.org/onlinedocs/gcc/Machine-Constraints.html
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg) => "w",
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg_low16) => "x",
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => {
+            unreachable!("clobber-only")
+        }
+        InlineAsmRegClass::Arm(ArmInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg)
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg_low16) => "t",
+        InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low16)
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low8)
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low8)
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low4)
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg)
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg) => "w",
+        InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_upper) => "d",
+        InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_pair) => "r",
+        InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_iw) => "w",
+        InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg_ptr) => "e",
+        InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::wreg) => "w",
+        InlineAsmRegClass::Hexagon(HexagonInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::LoongArch(LoongArchInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::LoongArch(LoongArchInlineAsmRegClass::freg) => "f",
+        InlineAsmRegClass::M68k(M68kInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::M68k(M68kInlineAsmRegClass::reg_addr) => "a",
+        InlineAsmRegClass::M68k(M68kInlineAsmRegClass::reg_data) => "d",
+        InlineAsmRegClass::CSKY(CSKYInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::CSKY(CSKYInlineAsmRegClass::freg) => "f",
+        InlineAsmRegClass::Mips(MipsInlineAsmRegClass::reg) => "d", // more specific than "r"
+        InlineAsmRegClass::Mips(MipsInlineAsmRegClass::freg) => "f",
+        InlineAsmRegClass::Msp430(Msp430InlineAsmRegClass::reg) => "r",
+        // https://github.com/gcc-mirror/gcc/blob/master/gcc/config/nvptx/nvptx.md -> look for
+        // "define_constraint".
+        InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg16) => "h",
+        InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg32) => "r",
+        InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg64) => "l",
+
+        InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg_nonzero) => "b",
+        InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::freg) => "f",
+        InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::cr)
+        | InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::xer) => {
+            unreachable!("clobber-only")
+        }
+        InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::freg) => "f",
+        InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => {
+            unreachable!("clobber-only")
+        }
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_abcd) => "Q",
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_byte) => "q",
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::xmm_reg)
+        | InlineAsmRegClass::X86(X86InlineAsmRegClass::ymm_reg) => "x",
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::zmm_reg) => "v",
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg) => "Yk",
+        InlineAsmRegClass::X86(
+            X86InlineAsmRegClass::kreg0
+            | X86InlineAsmRegClass::x87_reg
+            | X86InlineAsmRegClass::mmx_reg
+            | X86InlineAsmRegClass::tmm_reg,
+        ) => unreachable!("clobber-only"),
+        InlineAsmRegClass::SpirV(SpirVInlineAsmRegClass::reg) => {
+            bug!("GCC backend does not support SPIR-V")
+        }
+        InlineAsmRegClass::Wasm(WasmInlineAsmRegClass::local) => "r",
+        InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg) => "r",
+        InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg_addr) => "a",
+        InlineAsmRegClass::S390x(S390xInlineAsmRegClass::freg) => "f",
+        InlineAsmRegClass::Err => unreachable!(),
+    }
 }
 
 /// Type to use for outputs that are discarded. It doesn't really matter what
@@ -847,6 +903,144 @@ impl<'gcc, 'tcx> AsmMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
     }
 }
 
+impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
+    /// libgccjit does not support the `naked` attribute, so the naked functions are emitted as
+    /// global assembly containing the symbol definition.
+    pub fn codegen_naked_asm(&self, instance: Instance<'tcx>, item_data: &MonoItemData) {
+        let mir = self.tcx.instance_mir(instance.def);
+        let Some((template, operands, options, line_spans)) =
+            mir.basic_blocks.iter().find_map(|block| match block.terminator().kind {
+                TerminatorKind::InlineAsm {
+                    template, ref operands, options, line_spans, ..
+                } => Some((template, operands, options, line_spans)),
+                _ => None,
+            })
+        else {
+            bug!("naked function without an asm block");
+        };
+
+        let operands: Vec<_> =
+            operands.iter().map(|operand| self.naked_asm_operand(instance, operand)).collect();
+
+        let name = self.tcx.symbol_name(instance).name;
+        let (begin, end) = self.naked_asm_prefix_and_suffix(instance, name, item_data);
+
+        let mut template_pieces = Vec::with_capacity(template.len() + 2);
+        template_pieces.push(InlineAsmTemplatePiece::String(begin.into()));
+        template_pieces.extend(template.iter().cloned());
+        template_pieces.push(InlineAsmTemplatePiece::String(end.into()));
+        self.codegen_global_asm(&template_pieces, &operands, options, line_spans);
+    }
+
+    fn naked_asm_operand(
+        &self,
+        instance: Instance<'tcx>,
+        operand: &InlineAsmOperand<'tcx>,
+    ) -> GlobalAsmOperandRef<'tcx> {
+        match *operand {
+            InlineAsmOperand::Const { ref value } => {
+                let const_value = instance
+                    .instantiate_mir_and_normalize_erasing_regions(
+                        self.tcx,
+                        ty::ParamEnv::reveal_all(),
+                        ty::EarlyBinder::bind(value.const_),
+                    )
+                    .eval(self.tcx, ty::ParamEnv::reveal_all(), value.span)
+                    .expect("erroneous constant missed by mono item collection");
+                let ty = instance.instantiate_mir_and_normalize_erasing_regions(
+                    self.tcx,
+                    ty::ParamEnv::reveal_all(),
+                    ty::EarlyBinder::bind(value.ty()),
+                );
+                let string =
+                    asm_const_to_str(self.tcx, value.span, const_value, self.layout_of(ty));
+                GlobalAsmOperandRef::Const { string }
+            }
+            InlineAsmOperand::SymFn { ref value } => {
+                let ty = instance.instantiate_mir_and_normalize_erasing_regions(
+                    self.tcx,
+                    ty::ParamEnv::reveal_all(),
+                    ty::EarlyBinder::bind(value.ty()),
+                );
+                let instance = match *ty.kind() {
+                    ty::FnDef(def_id, args) => Instance::new(def_id, args),
+                    _ => span_bug!(value.span, "asm sym is not a function"),
+                };
+                GlobalAsmOperandRef::SymFn { instance }
+            }
+            InlineAsmOperand::SymStatic { def_id } => GlobalAsmOperandRef::SymStatic { def_id },
+            InlineAsmOperand::In { .. }
+            | InlineAsmOperand::Out { .. }
+            | InlineAsmOperand::InOut { .. }
+            | InlineAsmOperand::Label { .. } => {
+                bug!("invalid operand type for the asm of a naked function")
+            }
+        }
+    }
+
+    fn naked_asm_prefix_and_suffix(
+        &self,
+        instance: Instance<'tcx>,
+        name: &str,
+        item_data: &MonoItemData,
+    ) -> (String, String) {
+        let target = &self.tcx.sess.target;
+        let span = self.tcx.def_span(instance.def_id());
+        if !self.is_elf_target() {
+            self.tcx.dcx().emit_fatal(NakedFunctionUnsupportedTarget { span });
+        }
+
+        let attrs = self.tcx.codegen_fn_attrs(instance.def_id());
+        let is_arm = target.arch == "arm";
+        let is_thumb = self.tcx.sess.unstable_target_features.contains(&sym::thumb_mode);
+        // NOTE: on ARM, these directives allow the first instruction of the function to be
+        // recognized as a function start.
+        let (arch_prefix, arch_suffix) = if is_arm {
+            let prefix = match attrs.instruction_set {
+                Some(InstructionSetAttr::ArmT32) => ".thumb\n.thumb_func\n",
+                Some(InstructionSetAttr::ArmA32) => ".arm\n",
+                None if is_thumb => ".thumb\n.thumb_func\n",
+                None => ".arm\n",
+            };
+            (prefix, if is_thumb { ".thumb\n" } else { ".arm\n" })
+        } else {
+            ("", "")
+        };
+        // NOTE: `@` starts a comment on ARM.
+        let (progbits, function) =
+            if is_arm { ("%progbits", "%function") } else { ("@progbits", "@function") };
+
+        let section = attrs
+            .link_section
+            .map(|section| section.to_string())
+            .unwrap_or_else(|| format!(".text.{}", name));
+        let align = attrs.alignment.map(|align| align.bytes()).unwrap_or(4);
+
+        let mut begin =
+            format!(".pushsection {},\"ax\",{}\n.balign {}\n", section, progbits, align);
+        match item_data.linkage {
+            Linkage::External => begin.push_str(&format!(".globl {}\n", name)),
+            Linkage::LinkOnceAny | Linkage::LinkOnceODR | Linkage::WeakAny | Linkage::WeakODR => {
+                begin.push_str(&format!(".weak {}\n", name))
+            }
+            Linkage::Internal | Linkage::Private => (),
+            linkage => self.tcx.dcx().emit_fatal(NakedFunctionUnsupportedLinkage {
+                span,
+                linkage: format!("{:?}", linkage),
+            }),
+        }
+        match item_data.visibility {
+            Visibility::Default => (),
+            Visibility::Hidden => begin.push_str(&format!(".hidden {}\n", name)),
+            Visibility::Protected => begin.push_str(&format!(".protected {}\n", name)),
+        }
+        begin.push_str(&format!(".type {}, {}\n{}{}:\n", name, function, arch_prefix, name));
+
+        let end = format!("\n.size {}, . - {}\n.popsection\n{}", name, name, arch_suffix);
+        (begin, end)
+    }
+}
+
 fn modifier_to_gcc(
     arch: InlineAsmArch,
     reg: InlineAsmRegClass,
@@ -10,8 +10,8 @@ use rustc_middle::ty;
 use rustc_span::symbol::sym;
 
 use crate::context::CodegenCx;
-use crate::errors::TiedTargetFeatures;
-use crate::gcc_util::{check_tied_features, to_gcc_features};
+use crate::errors::{NoSanitizeUnsupported, TiedTargetFeatures};
+use crate::gcc_util::{check_tied_features, to_gcc_features, to_gcc_target_attribute};
 
 /// Get GCC attribute for the provided inline heuristic.
 #[cfg(feature = "master")]
@@ -45,9 +45,8 @@ pub fn from_fn_attrs<'gcc, 'tcx>(
 
     #[cfg(feature = "master")]
     {
-        let inline = if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NAKED) {
-            InlineAttr::Never
-        } else if codegen_fn_attrs.inline == InlineAttr::None
+        // NOTE: naked functions are emitted as global asm, so they never reach this function.
+        let inline = if codegen_fn_attrs.inline == InlineAttr::None
             && instance.def.requires_inline(cx.tcx)
         {
             InlineAttr::Hint
@@ -72,6 +71,21 @@ pub fn from_fn_attrs<'gcc, 'tcx>(
         }
     }
 
+    // TODO: use the no_sanitize attribute when libgccjit supports it. Until then, this is an error
+    // since the function would still be instrumented.
+    let unsupported_sanitizers =
+        cx.sess().opts.unstable_opts.sanitizer & codegen_fn_attrs.no_sanitize;
+    if !unsupported_sanitizers.is_empty() && instance.def_id().is_local() {
+        let span = cx
+            .tcx
+            .get_attr(instance.def_id(), sym::no_sanitize)
+            .map_or_else(|| cx.tcx.def_span(instance.def_id()), |a| a.span);
+        cx.tcx.dcx().emit_err(NoSanitizeUnsupported {
+            span,
+            sanitizers: unsupported_sanitizers.to_string(),
+        });
+    }
+
     let function_features = codegen_fn_attrs
         .target_features
         .iter()
@@ -94,47 +108,35 @@ pub fn from_fn_attrs<'gcc, 'tcx>(
         .iter()
         .flat_map(|feat| to_gcc_features(cx.tcx.sess, feat).into_iter())
         .chain(codegen_fn_attrs.instruction_set.iter().map(|x| match *x {
-            InstructionSetAttr::ArmA32 => "-thumb-mode", // TODO(antoyo): support removing feature.
+            InstructionSetAttr::ArmA32 => "-thumb-mode",
             InstructionSetAttr::ArmT32 => "thumb-mode",
         }))
         .collect::<Vec<_>>();
 
     // TODO(antoyo): cg_llvm adds global features to each function so that LTO keep them.
     // Check if GCC requires the same.
+    // NOTE: only -march=<target-cpu> is passed on the command line, so the global features are
+    // applied to each function. The features that cannot be set on a single function are
+    // dropped by to_gcc_target_attribute().
     let mut global_features = cx.tcx.global_backend_features(()).iter().map(|s| s.as_str());
     function_features.extend(&mut global_features);
-    let target_features = function_features
-        .iter()
-        .filter_map(|feature| {
-            // FIXME(antoyo): for some reasons, disabling SSE results in the following error when
-            // compiling Rust for Linux:
+    let function_features = function_features
+        .into_iter()
+        .filter(|feature| {
+            // NOTE: disabling SSE results in the following error when the floating-point values
+            // are passed in the SSE registers:
             // SSE register return with SSE disabled
-            // TODO(antoyo): support soft-float and retpoline-external-thunk.
-            if feature.contains("soft-float")
-                || feature.contains("retpoline-external-thunk")
-                || *feature == "-sse"
-            {
-                return None;
-            }
-
-            if feature.starts_with('-') {
-                Some(format!("no{}", feature))
-            } else if let Some(stripped) = feature.strip_prefix('+') {
-                Some(stripped.to_string())
-            } else {
-                Some(feature.to_string())
-            }
+            // This is only allowed with soft-float, where they are passed as integers.
+            // NOTE: soft-float and the retpoline features are handled by command-line options in
+            // new_context().
+            !(feature.contains("soft-float")
+                || feature.contains("retpoline")
+                || (*feature == "-sse" && !cx.soft_float))
         })
-        .collect::<Vec<_>>()
-        .join(",");
+        .collect::<Vec<_>>();
+    let target_features = to_gcc_target_attribute(cx.sess(), &function_features);
     if !target_features.is_empty() {
         #[cfg(feature = "master")]
-        match cx.sess().target.arch.as_ref() {
-            "x86" | "x86_64" | "powerpc" => {
-                func.add_attribute(FnAttribute::Target(&target_features))
-            }
-            // The target attribute is not supported on other targets in GCC.
-            _ => (),
-        }
+        func.add_attribute(FnAttribute::Target(&target_features));
     }
 }
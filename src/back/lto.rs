@@ -278,7 +278,9 @@ fn fat_lto(
 
         // We cannot load and merge GCC contexts in memory like cg_llvm is doing.
         // Instead, we combine the object files into a single object file.
+        let mut function_link_sections = vec![];
         for module in in_memory {
+            function_link_sections.extend(module.module_llvm.function_link_sections);
             let path = tmp_path.path().to_path_buf().join(&module.name);
             let path = path.to_str().expect("path");
             let context = &module.module_llvm.context;
@@ -292,6 +294,7 @@ fn fat_lto(
             let llmod_id = CString::new(&module.name[..]).unwrap();
             serialized_modules.push((SerializedModule::Local(buffer), llmod_id));
         }
+        module.module_llvm.function_link_sections.extend(function_link_sections);
         // Sort the modules to ensure we produce deterministic results.
         serialized_modules.sort_by(|module1, module2| module1.1.cmp(&module2.1));
 
@@ -389,7 +392,7 @@ pub(crate) fn prepare_thin(
 ) -> (String, ThinBuffer) {
     let name = module.name;
     //let buffer = ThinBuffer::new(module.module_llvm.context, true, emit_summary);
-    let buffer = ThinBuffer::new(&module.module_llvm.context);
+    let buffer = ThinBuffer::new(&module.module_llvm);
     (name, buffer)
 }
 
@@ -616,8 +619,12 @@ pub unsafe fn optimize_thin_module(
     //let llcx = llvm::LLVMRustContextCreate(cgcx.fewer_names);
     //let llmod_raw = parse_module(llcx, module_name, thin_module.data(), &dcx)? as *const _;
     let mut should_combine_object_files = false;
+    let mut function_link_sections = vec![];
     let context = match thin_module.shared.thin_buffers.get(thin_module.idx) {
-        Some(thin_buffer) => Arc::clone(&thin_buffer.context),
+        Some(thin_buffer) => {
+            function_link_sections = thin_buffer.function_link_sections.clone();
+            Arc::clone(&thin_buffer.context)
+        }
         None => {
             let context = Context::default();
             let len = thin_module.shared.thin_buffers.len();
@@ -642,7 +649,12 @@ pub unsafe fn optimize_thin_module(
         }
     };
     let module = ModuleCodegen {
-        module_llvm: GccContext { context, should_combine_object_files, temp_dir: None },
+        module_llvm: GccContext {
+            context,
+            should_combine_object_files,
+            function_link_sections,
+            temp_dir: None,
+        },
         name: thin_module.name().to_string(),
         kind: ModuleKind::Regular,
     };
@@ -713,6 +725,7 @@ pub unsafe fn optimize_thin_module(
 
 pub struct ThinBuffer {
     context: Arc<SyncContext>,
+    function_link_sections: Vec<(String, String)>,
 }
 
 // TODO: check if this makes sense to make ThinBuffer Send and Sync.
@@ -720,8 +733,11 @@ unsafe impl Send for ThinBuffer {}
 unsafe impl Sync for ThinBuffer {}
 
 impl ThinBuffer {
-    pub(crate) fn new(context: &Arc<SyncContext>) -> Self {
-        Self { context: Arc::clone(context) }
+    pub(crate) fn new(module: &GccContext) -> Self {
+        Self {
+            context: Arc::clone(&module.context),
+            function_link_sections: module.function_link_sections.clone(),
+        }
     }
 }
 
@@ -1,6 +1,10 @@
+use std::path::Path;
 use std::{env, fs};
 
 use gccjit::OutputKind;
+use object::elf::{self, FileHeader32, FileHeader64};
+use object::read::elf::{FileHeader, SectionHeader, Sym};
+use object::{Endianness, FileKind, SectionIndex};
 use rustc_codegen_ssa::back::link::ensure_removed;
 use rustc_codegen_ssa::back::write::{BitcodeSection, CodegenContext, EmitObj, ModuleConfig};
 use rustc_codegen_ssa::{CompiledModule, ModuleCodegen};
@@ -10,7 +14,7 @@ use rustc_session::config::OutputType;
 use rustc_span::fatal_error::FatalError;
 use rustc_target::spec::SplitDebuginfo;
 
-use crate::errors::CopyBitcode;
+use crate::errors::{CopyBitcode, FunctionLinkSectionNotFound, SetFunctionLinkSections};
 use crate::{GccCodegenBackend, GccContext};
 
 pub(crate) unsafe fn codegen(
@@ -26,6 +30,7 @@ pub(crate) unsafe fn codegen(
         let module_name = module.name.clone();
 
         let should_combine_object_files = module.module_llvm.should_combine_object_files;
+        let function_link_sections = &module.module_llvm.function_link_sections;
 
         let module_name = Some(&module_name[..]);
 
@@ -89,6 +94,9 @@ pub(crate) unsafe fn codegen(
                 cgcx.prof.generic_activity_with_arg("GCC_module_codegen_emit_asm", &*module.name);
             let path = cgcx.output_filenames.temp_path(OutputType::Assembly, module_name);
             context.compile_to_file(OutputKind::Assembler, path.to_str().expect("path to str"));
+            if !function_link_sections.is_empty() {
+                set_asm_function_link_sections(dcx, &path, function_link_sections);
+            }
         }
 
         match config.emit_obj {
@@ -142,6 +150,9 @@ pub(crate) unsafe fn codegen(
                         obj_out.to_str().expect("path to str"),
                     );
                 }
+                if !function_link_sections.is_empty() {
+                    set_function_link_sections(dcx, &obj_out, function_link_sections);
+                }
             }
 
             EmitObj::Bitcode => {
@@ -170,6 +181,237 @@ pub(crate) unsafe fn codegen(
     ))
 }
 
+/// libgccjit cannot set the section of a function, so the functions with a `#[link_section]` are
+/// compiled in their own section (`-ffunction-sections`), which is renamed in the ELF object file.
+fn set_function_link_sections(
+    dcx: DiagCtxtHandle<'_>,
+    path: &Path,
+    function_link_sections: &[(String, String)],
+) {
+    let path_str = path.to_str().expect("path to str");
+    let mut data = match fs::read(path) {
+        Ok(data) => data,
+        Err(err) => {
+            dcx.emit_err(SetFunctionLinkSections { path: path_str, err: err.to_string() });
+            return;
+        }
+    };
+    let result = match FileKind::parse(&*data) {
+        Ok(FileKind::Elf32) => {
+            rename_elf_sections::<FileHeader32<Endianness>>(&mut data, function_link_sections)
+        }
+        Ok(FileKind::Elf64) => {
+            rename_elf_sections::<FileHeader64<Endianness>>(&mut data, function_link_sections)
+        }
+        Ok(_) => Err("not an ELF object file".into()),
+        Err(err) => Err(err.into()),
+    };
+    let missing = match result {
+        Ok(missing) => missing,
+        Err(err) => {
+            dcx.emit_err(SetFunctionLinkSections { path: path_str, err: err.to_string() });
+            return;
+        }
+    };
+    if let Err(err) = fs::write(path, data) {
+        dcx.emit_err(SetFunctionLinkSections { path: path_str, err: err.to_string() });
+        return;
+    }
+    for (function, missing) in function_link_sections.iter().zip(missing) {
+        if missing {
+            dcx.emit_warn(FunctionLinkSectionNotFound { symbol: &function.0, path: path_str });
+        }
+    }
+}
+
+/// Rename the sections of the functions with a `#[link_section]` by appending the new names to a
+/// copy of the section header string table, and return which of those functions are defined in
+/// the object file, but not in their own section.
+fn rename_elf_sections<Elf>(
+    data: &mut Vec<u8>,
+    function_link_sections: &[(String, String)],
+) -> Result<Vec<bool>, Box<dyn std::error::Error>>
+where
+    Elf: FileHeader<Endian = Endianness>,
+    Elf::SectionHeader: SectionHeaderMut,
+{
+    let header = Elf::parse(&**data)?;
+    let endian = header.endian()?;
+    let sections = header.sections(endian, &**data)?;
+    let string_table_index = header.shstrndx(endian, &**data)? as usize;
+    let mut string_table =
+        sections.section(SectionIndex(string_table_index))?.data(endian, &**data)?.to_vec();
+
+    let mut found = vec![false; function_link_sections.len()];
+    let mut renamed_sections = vec![];
+    for (index, section) in sections.enumerate() {
+        let name = sections.section_name(endian, section)?;
+        let Some((function_index, new_name)) = std::str::from_utf8(name)
+            .ok()
+            .and_then(|name| renamed_function_section(name, function_link_sections))
+        else {
+            continue;
+        };
+        found[function_index] = true;
+        // NOTE: like with the LLVM backend, the section keeps the flags of a code section, but
+        // its type depends on its name.
+        let mut section_type = section.sh_type(endian);
+        if section_type == elf::SHT_PROGBITS
+            && let Some((new_type, _)) = link_section_type(&new_name)
+        {
+            section_type = new_type;
+        }
+        renamed_sections.push((index.0, string_table.len() as u32, section_type));
+        string_table.extend_from_slice(new_name.as_bytes());
+        string_table.push(0);
+    }
+
+    // NOTE: the functions that were inlined in all of their callers are not emitted at all, so
+    // only the functions that are defined in the object file are missing their section.
+    let mut missing = vec![false; function_link_sections.len()];
+    let symbols = sections.symbols(endian, &**data, elf::SHT_SYMTAB)?;
+    for symbol in symbols.iter() {
+        if symbol.is_undefined(endian) {
+            continue;
+        }
+        let name = symbols.symbol_name(endian, symbol)?;
+        if let Some(index) =
+            function_link_sections.iter().position(|function| function.0.as_bytes() == name)
+        {
+            missing[index] = !found[index];
+        }
+    }
+
+    if renamed_sections.is_empty() {
+        return Ok(missing);
+    }
+    let section_headers_offset = header.e_shoff(endian).into() as usize;
+    let section_count = sections.len();
+    let string_table_offset = data.len() as u64;
+    let string_table_size = string_table.len() as u64;
+    data.extend_from_slice(&string_table);
+
+    let (section_headers, _) = object::pod::slice_from_bytes_mut::<Elf::SectionHeader>(
+        data.get_mut(section_headers_offset..).ok_or("invalid section headers")?,
+        section_count,
+    )
+    .map_err(|()| "invalid section headers")?;
+    for (index, name, section_type) in renamed_sections {
+        section_headers[index].set_name_and_type(endian, name, section_type);
+    }
+    section_headers[string_table_index].set_data(endian, string_table_offset, string_table_size);
+    Ok(missing)
+}
+
+/// The object crate can only read the fields of the section headers.
+trait SectionHeaderMut {
+    fn set_name_and_type(&mut self, endian: Endianness, name: u32, section_type: u32);
+    fn set_data(&mut self, endian: Endianness, offset: u64, size: u64);
+}
+
+impl SectionHeaderMut for elf::SectionHeader32<Endianness> {
+    fn set_name_and_type(&mut self, endian: Endianness, name: u32, section_type: u32) {
+        self.sh_name.set(endian, name);
+        self.sh_type.set(endian, section_type);
+    }
+
+    fn set_data(&mut self, endian: Endianness, offset: u64, size: u64) {
+        self.sh_offset.set(endian, offset as u32);
+        self.sh_size.set(endian, size as u32);
+    }
+}
+
+impl SectionHeaderMut for elf::SectionHeader64<Endianness> {
+    fn set_name_and_type(&mut self, endian: Endianness, name: u32, section_type: u32) {
+        self.sh_name.set(endian, name);
+        self.sh_type.set(endian, section_type);
+    }
+
+    fn set_data(&mut self, endian: Endianness, offset: u64, size: u64) {
+        self.sh_offset.set(endian, offset);
+        self.sh_size.set(endian, size);
+    }
+}
+
+/// Same as `set_function_link_sections()`, but for the `.section` directives of an assembly file.
+fn set_asm_function_link_sections(
+    dcx: DiagCtxtHandle<'_>,
+    path: &Path,
+    function_link_sections: &[(String, String)],
+) {
+    let path_str = path.to_str().expect("path to str");
+    let asm = match fs::read_to_string(path) {
+        Ok(asm) => asm,
+        Err(err) => {
+            dcx.emit_err(SetFunctionLinkSections { path: path_str, err: err.to_string() });
+            return;
+        }
+    };
+    let mut new_asm = String::with_capacity(asm.len());
+    for line in asm.split_inclusive('\n') {
+        let directive = line.trim_start();
+        let indentation = &line[..line.len() - directive.len()];
+        if let Some(arguments) = directive.strip_prefix(".section") {
+            let arguments = arguments.trim_start();
+            let name_len = arguments.find([',', ' ', '\t', '\n']).unwrap_or(arguments.len());
+            if let Some((_, section)) =
+                renamed_function_section(&arguments[..name_len], function_link_sections)
+            {
+                let mut arguments = arguments[name_len..].to_string();
+                if let Some((_, typ)) = link_section_type(&section) {
+                    // NOTE: the type is prefixed by `@` or `%` depending on the architecture.
+                    arguments = arguments.replacen("progbits", typ, 1);
+                }
+                new_asm.push_str(indentation);
+                new_asm.push_str(".section\t");
+                new_asm.push_str(&section);
+                new_asm.push_str(&arguments);
+                continue;
+            }
+        }
+        new_asm.push_str(line);
+    }
+    if let Err(err) = fs::write(path, new_asm) {
+        dcx.emit_err(SetFunctionLinkSections { path: path_str, err: err.to_string() });
+    }
+}
+
+/// Get the new name of a section created by `-ffunction-sections` for a function with a
+/// `#[link_section]`, as well as the index of this function.
+fn renamed_function_section(
+    name: &str,
+    function_link_sections: &[(String, String)],
+) -> Option<(usize, String)> {
+    // NOTE: the relocation sections are renamed as well to keep the same naming as GCC.
+    let (prefix, name) = [".rela", ".rel"]
+        .iter()
+        .find_map(|&prefix| Some((prefix, name.strip_prefix(prefix)?)))
+        .unwrap_or(("", name));
+    let name = name.strip_prefix(".text.")?;
+    // NOTE: GCC can move a function (or its cold part) in those sections.
+    let name = [".unlikely.", ".hot.", ".startup.", ".exit."]
+        .iter()
+        .find_map(|subsection| name.strip_prefix(&subsection[1..]))
+        .unwrap_or(name);
+    let index = function_link_sections.iter().position(|function| function.0 == name)?;
+    Some((index, format!("{}{}", prefix, function_link_sections[index].1)))
+}
+
+/// Get the ELF section type (and its name in the assembly) of the sections that are not
+/// `SHT_PROGBITS` because of their name, as done by GCC and LLVM.
+fn link_section_type(section: &str) -> Option<(u32, &'static str)> {
+    const SECTION_TYPES: [(&str, u32, &str); 4] = [
+        (".init_array", elf::SHT_INIT_ARRAY, "init_array"),
+        (".fini_array", elf::SHT_FINI_ARRAY, "fini_array"),
+        (".preinit_array", elf::SHT_PREINIT_ARRAY, "preinit_array"),
+        (".note", elf::SHT_NOTE, "note"),
+    ];
+    SECTION_TYPES
+        .iter()
+        .find(|&&(prefix, _, _)| section.starts_with(prefix))
+        .map(|&(_, typ, name)| (typ, name))
+}
+
 pub(crate) fn link(
     _cgcx: &CodegenContext<GccCodegenBackend>,
     _dcx: DiagCtxtHandle<'_>,
@@ -9,9 +9,10 @@ use rustc_codegen_ssa::mono_item::MonoItemExt;
 use rustc_codegen_ssa::traits::DebugInfoMethods;
 use rustc_codegen_ssa::{ModuleCodegen, ModuleKind};
 use rustc_middle::dep_graph;
-use rustc_middle::mir::mono::Linkage;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 #[cfg(feature = "master")]
 use rustc_middle::mir::mono::Visibility;
+use rustc_middle::mir::mono::{Linkage, MonoItem};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::DebugInfo;
 use rustc_span::Symbol;
@@ -30,37 +31,58 @@ pub fn visibility_to_gcc(linkage: Visibility) -> gccjit::Visibility {
     }
 }
 
-pub fn global_linkage_to_gcc(linkage: Linkage) -> GlobalKind {
-    match linkage {
+/// Returns `None` for the linkages that cannot be expressed with libgccjit.
+/// NOTE: the weak linkages are implemented by the `weak` attribute: see `linkage_is_weak()`.
+pub fn global_linkage_to_gcc(linkage: Linkage) -> Option<GlobalKind> {
+    let kind = match linkage {
         Linkage::External => GlobalKind::Imported,
         Linkage::AvailableExternally => GlobalKind::Imported,
-        Linkage::LinkOnceAny => unimplemented!(),
-        Linkage::LinkOnceODR => unimplemented!(),
-        Linkage::WeakAny => unimplemented!(),
-        Linkage::WeakODR => unimplemented!(),
-        Linkage::Appending => unimplemented!(),
+        Linkage::LinkOnceAny => GlobalKind::Exported,
+        Linkage::LinkOnceODR => GlobalKind::Exported,
+        Linkage::WeakAny => GlobalKind::Exported,
+        Linkage::WeakODR => GlobalKind::Exported,
+        Linkage::Appending => return None,
         Linkage::Internal => GlobalKind::Internal,
         Linkage::Private => GlobalKind::Internal,
-        Linkage::ExternalWeak => GlobalKind::Imported, // TODO(antoyo): should be weak linkage.
-        Linkage::Common => unimplemented!(),
-    }
+        Linkage::ExternalWeak => GlobalKind::Imported,
+        // NOTE: libgccjit cannot create common symbols, so they are emitted as weak definitions,
+        // which are merged the same way by the linker.
+        Linkage::Common => GlobalKind::Exported,
+    };
+    Some(kind)
 }
 
-pub fn linkage_to_gcc(linkage: Linkage) -> FunctionType {
-    match linkage {
+/// Returns `None` for the linkages that cannot be expressed with libgccjit.
+/// NOTE: the weak linkages are implemented by the `weak` attribute: see `linkage_is_weak()`.
+pub fn linkage_to_gcc(linkage: Linkage) -> Option<FunctionType> {
+    let function_type = match linkage {
         Linkage::External => FunctionType::Exported,
         // TODO(antoyo): set the attribute externally_visible.
         Linkage::AvailableExternally => FunctionType::Extern,
-        Linkage::LinkOnceAny => unimplemented!(),
-        Linkage::LinkOnceODR => unimplemented!(),
-        Linkage::WeakAny => FunctionType::Exported, // FIXME(antoyo): should be similar to linkonce.
-        Linkage::WeakODR => unimplemented!(),
-        Linkage::Appending => unimplemented!(),
+        // TODO: use a comdat when libgccjit supports it instead of making those symbols weak.
+        Linkage::LinkOnceAny => FunctionType::Exported,
+        Linkage::LinkOnceODR => FunctionType::Exported,
+        Linkage::WeakAny => FunctionType::Exported,
+        Linkage::WeakODR => FunctionType::Exported,
+        Linkage::Appending | Linkage::Common => return None,
         Linkage::Internal => FunctionType::Internal,
         Linkage::Private => FunctionType::Internal,
-        Linkage::ExternalWeak => unimplemented!(),
-        Linkage::Common => unimplemented!(),
-    }
+        Linkage::ExternalWeak => FunctionType::Extern,
+    };
+    Some(function_type)
+}
+
+/// Whether the symbol needs the `weak` attribute to get the semantics of this linkage.
+pub fn linkage_is_weak(linkage: Linkage) -> bool {
+    matches!(
+        linkage,
+        Linkage::LinkOnceAny
+            | Linkage::LinkOnceODR
+            | Linkage::WeakAny
+            | Linkage::WeakODR
+            | Linkage::ExternalWeak
+            | Linkage::Common
+    )
 }
 
 pub fn compile_codegen_unit(
@@ -109,7 +131,10 @@ pub fn compile_codegen_unit(
             .map(|string| &string[1..])
             .collect();
 
-        if !disabled_features.contains("avx") && tcx.sess.target.arch == "x86_64" {
+        if !disabled_features.contains("avx")
+            && tcx.sess.target.arch == "x86_64"
+            && !gcc_util::has_soft_float(tcx.sess)
+        {
             // NOTE: we always enable AVX because the equivalent of llvm.x86.sse2.cmp.pd in GCC for
             // SSE2 is multiple builtins, so we use the AVX __builtin_ia32_cmppd instead.
             // FIXME(antoyo): use the proper builtins for llvm.x86.sse2.cmp.pd and similar.
@@ -140,8 +165,15 @@ pub fn compile_codegen_unit(
             });
         }
 
-        if tcx.sess.relocation_model() == rustc_target::spec::RelocModel::Static {
-            context.add_command_line_option("-fno-pie");
+        // NOTE: the sanitizer runtimes are linked by rustc when linking the final artifact.
+        for sanitizer in tcx.sess.opts.unstable_opts.sanitizer {
+            // Unsupported sanitizers are reported when initializing the backend.
+            if let Some(name) = gcc_util::sanitizer_to_gcc(sanitizer) {
+                context.add_command_line_option(format!("-fsanitize={}", name));
+                if tcx.sess.opts.unstable_opts.sanitizer_recover.contains(sanitizer) {
+                    context.add_command_line_option(format!("-fsanitize-recover={}", name));
+                }
+            }
         }
 
         let target_cpu = gcc_util::target_cpu(tcx.sess);
@@ -191,7 +223,7 @@ pub fn compile_codegen_unit(
         // NOTE: The codegen generates unreachable blocks.
         context.set_allow_unreachable_blocks(true);
 
-        {
+        let function_link_sections = {
             // TODO: to make it less error-prone (calling get_target_info() will add the flag
             // -fsyntax-only), forbid the compilation when get_target_info() is called on a
             // context.
@@ -210,6 +242,7 @@ pub fn compile_codegen_unit(
                 f32_type_supported,
                 f64_type_supported,
                 f128_type_supported,
+                target_info,
             );
 
             let mono_items = cgu.items_in_deterministic_order(tcx);
@@ -218,7 +251,17 @@ pub fn compile_codegen_unit(
             }
 
             // ... and now that we have everything pre-defined, fill out those definitions.
-            for &(mono_item, _) in &mono_items {
+            for &(mono_item, ref data) in &mono_items {
+                if let MonoItem::Fn(instance) = mono_item {
+                    if tcx
+                        .codegen_fn_attrs(instance.def_id())
+                        .flags
+                        .contains(CodegenFnAttrFlags::NAKED)
+                    {
+                        cx.codegen_naked_asm(instance, data);
+                        continue;
+                    }
+                }
                 mono_item.define::<Builder<'_, '_, '_>>(&cx);
             }
 
@@ -230,13 +273,16 @@ pub fn compile_codegen_unit(
             if cx.sess().opts.debuginfo != DebugInfo::None {
                 cx.debuginfo_finalize();
             }
-        }
+
+            cx.function_link_sections.take()
+        };
 
         ModuleCodegen {
             name: cgu_name.to_string(),
             module_llvm: GccContext {
                 context: Arc::new(SyncContext::new(context)),
                 should_combine_object_files: false,
+                function_link_sections,
                 temp_dir: None,
             },
             kind: ModuleKind::Regular,
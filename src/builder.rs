@@ -28,7 +28,7 @@ use rustc_middle::ty::layout::{
 use rustc_middle::ty::{Instance, ParamEnv, Ty, TyCtxt};
 use rustc_span::def_id::DefId;
 use rustc_span::Span;
-use rustc_target::abi::call::FnAbi;
+use rustc_target::abi::call::{FnAbi, PassMode};
 use rustc_target::abi::{self, Align, HasDataLayout, Size, TargetDataLayout, WrappingRange};
 use rustc_target::spec::{HasTargetSpec, HasWasmCAbiOpt, Target, WasmCAbi};
 
@@ -364,6 +364,12 @@ impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
                 new_func_type
             }
         };
+        if let Some(original_function_name) = self.function_address_names.borrow().get(&func_ptr)
+            && llvm::amx_tile_intrinsic(self, original_function_name, args)
+        {
+            // Return dummy value since these intrinsics don't return anything.
+            return self.context.new_rvalue_zero(self.isize_type);
+        }
         let func_name = format!("{:?}", func_ptr);
         let previous_arg_count = args.len();
         let orig_args = args;
@@ -389,6 +395,11 @@ impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
         let current_func = self.block.get_function();
 
         if return_type != void_type {
+            // NOTE: typ is the type of the LLVM declaration while gcc_func can be the type of the
+            // GCC builtin it was replaced with.
+            let declared_return_type = typ
+                .dyncast_function_ptr_type()
+                .map_or(return_type, |func_type| func_type.get_return_type());
             let return_value = self.cx.context.new_call_through_ptr(self.location, func_ptr, &args);
             let return_value = llvm::adjust_intrinsic_return_value(
                 self,
@@ -397,6 +408,7 @@ impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
                 &args,
                 args_adjusted,
                 orig_args,
+                declared_return_type,
             );
             let result = current_func.new_local(
                 self.location,
@@ -558,6 +570,21 @@ impl<'a, 'gcc, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'gcc, 'tcx> {
             value = value.dereference(self.location).to_rvalue();
         }
         let expected_return_type = self.current_func().get_return_type();
+        if self.soft_float
+            && self.is_float_type(value.get_type())
+            && !self.is_float_type(expected_return_type)
+        {
+            // NOTE: with soft-float, the floating-point values are returned as integers.
+            value = self.bitcast(value, self.soft_float_abi_type(value.get_type()));
+        }
+        if self.soft_float
+            && value.get_type().is_struct().is_some()
+            && !expected_return_type.is_compatible_with(value.get_type())
+        {
+            // NOTE: with soft-float, the pairs containing floating-point values are returned as a
+            // struct of integers.
+            value = self.transmute_through_memory(value, expected_return_type);
+        }
         if !expected_return_type.is_compatible_with(value.get_type()) {
             // NOTE: due to opaque pointers now being used, we need to cast here.
             value = self.context.new_cast(self.location, value, expected_return_type);
@@ -593,7 +620,7 @@ impl<'a, 'gcc, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'gcc, 'tcx> {
         &mut self,
         typ: Type<'gcc>,
         fn_attrs: Option<&CodegenFnAttrs>,
-        _fn_abi: Option<&FnAbi<'tcx, Ty<'tcx>>>,
+        fn_abi: Option<&FnAbi<'tcx, Ty<'tcx>>>,
         func: RValue<'gcc>,
         args: &[RValue<'gcc>],
         then: Block<'gcc>,
@@ -605,7 +632,7 @@ impl<'a, 'gcc, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'gcc, 'tcx> {
 
         let current_block = self.block;
         self.block = try_block;
-        let call = self.call(typ, fn_attrs, None, func, args, None, instance); // TODO(antoyo): use funclet here?
+        let call = self.call(typ, fn_attrs, fn_abi, func, args, None, instance); // TODO(antoyo): use funclet here?
         self.block = current_block;
 
         let return_value =
@@ -639,12 +666,9 @@ impl<'a, 'gcc, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'gcc, 'tcx> {
         _funclet: Option<&Funclet>,
         instance: Option<Instance<'tcx>>,
     ) -> RValue<'gcc> {
-        let call_site = self.call(typ, fn_attrs, None, func, args, None, instance);
+        let call_site = self.call(typ, fn_attrs, fn_abi, func, args, None, instance);
         let condition = self.context.new_rvalue_from_int(self.bool_type, 1);
         self.llbb().end_with_conditional(self.location, condition, then, catch);
-        if let Some(_fn_abi) = fn_abi {
-            // TODO(bjorn3): Apply function attributes
-        }
         call_site
     }
 
@@ -932,8 +956,22 @@ impl<'a, 'gcc, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'gcc, 'tcx> {
             .get_address(self.location)
     }
 
-    fn dynamic_alloca(&mut self, _len: RValue<'gcc>, _align: Align) -> RValue<'gcc> {
-        unimplemented!();
+    fn dynamic_alloca(&mut self, len: RValue<'gcc>, align: Align) -> RValue<'gcc> {
+        let len = self.intcast(len, self.type_size_t(), false);
+        // NOTE: the alignment of __builtin_alloca_with_align is in bits.
+        let align = self.context.new_rvalue_from_long(self.type_size_t(), align.bits() as i64);
+        let alloca = self.context.get_builtin_function("__builtin_alloca_with_align");
+        let ptr = self.context.new_call(self.location, alloca, &[len, align]);
+        // NOTE: like the allocas of LLVM, the memory is only freed when the function returns.
+        // The result is assigned to a variable so that the allocation happens here instead of
+        // where the pointer is used.
+        let result = self.current_func().new_local(
+            self.location,
+            ptr.get_type(),
+            format!("dynamic_stack_var_{}", self.next_value_counter()),
+        );
+        self.block.add_assignment(self.location, result, ptr);
+        result.to_rvalue()
     }
 
     fn load(&mut self, pointee_ty: Type<'gcc>, ptr: RValue<'gcc>, align: Align) -> RValue<'gcc> {
@@ -1678,14 +1716,30 @@ impl<'a, 'gcc, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'gcc, 'tcx> {
     ) -> RValue<'gcc> {
         // FIXME(antoyo): remove when having a proper API.
         let gcc_func = unsafe { std::mem::transmute::<RValue<'gcc>, Function<'gcc>>(func) };
-        let call = if self.functions.borrow().values().any(|value| *value == gcc_func) {
+        let mut call = if self.functions.borrow().values().any(|value| *value == gcc_func) {
             self.function_call(func, args, funclet)
         } else {
             // If it's a not function that was defined, it's a function pointer.
             self.function_ptr_call(typ, func, args, funclet)
         };
-        if let Some(_fn_abi) = fn_abi {
+        if let Some(fn_abi) = fn_abi {
             // TODO(bjorn3): Apply function attributes
+            if self.soft_float {
+                let return_type = fn_abi.ret.layout.immediate_gcc_type(self.cx);
+                match fn_abi.ret.mode {
+                    // NOTE: with soft-float, the floating-point values are returned as integers.
+                    PassMode::Direct(_)
+                        if self.is_float_type(return_type)
+                            && !self.is_float_type(call.get_type()) =>
+                    {
+                        call = self.bitcast(call, return_type);
+                    }
+                    PassMode::Pair(..) if !return_type.is_compatible_with(call.get_type()) => {
+                        call = self.transmute_through_memory(call, return_type);
+                    }
+                    _ => (),
+                }
+            }
         }
         call
     }
@@ -1740,6 +1794,19 @@ impl<'a, 'gcc, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'gcc, 'tcx> {
 }
 
 impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
+    /// Reinterprets `value` as a value of the type `typ` of the same size by going through memory,
+    /// since the struct types cannot be cast.
+    fn transmute_through_memory(&mut self, value: RValue<'gcc>, typ: Type<'gcc>) -> RValue<'gcc> {
+        let local = self.current_func().new_local(self.location, value.get_type(), "transmuted");
+        self.llbb().add_assignment(self.location, local, value);
+        let pointer = self.context.new_cast(
+            self.location,
+            local.get_address(self.location),
+            typ.make_pointer(),
+        );
+        pointer.dereference(self.location).to_rvalue()
+    }
+
     fn fptoint_sat(
         &mut self,
         signed: bool,
@@ -2022,6 +2089,9 @@ impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
         unimplemented!();
     }
 
+    /// Reduce the vector by combining it with rotations of itself.
+    /// NOTE: when the number of elements is not a power of two, some elements are combined more
+    /// than once, so `op` must be idempotent (e.g. min and max) in this case.
     #[cfg(feature = "master")]
     pub fn vector_reduce<F>(&mut self, src: RValue<'gcc>, op: F) -> RValue<'gcc>
     where
@@ -2059,77 +2129,98 @@ impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
             .to_rvalue()
     }
 
+    /// Reduce the elements of the vector one after the other.
+    /// NOTE: without vector permutations, each element is broadcast to a whole vector since `op`
+    /// works on vectors, and the result accumulates in the first lane.
     #[cfg(not(feature = "master"))]
-    pub fn vector_reduce<F>(&mut self, _src: RValue<'gcc>, _op: F) -> RValue<'gcc>
+    pub fn vector_reduce<F>(&mut self, src: RValue<'gcc>, op: F) -> RValue<'gcc>
     where
         F: Fn(RValue<'gcc>, RValue<'gcc>, &'gcc Context<'gcc>) -> RValue<'gcc>,
     {
-        unimplemented!();
+        let vector_type = src.get_type().unqualified();
+        let elements = self.vector_elements(src);
+        let element_count = elements.len();
+        let mut res = src;
+        for element in elements.into_iter().skip(1) {
+            let broadcast = self.context.new_rvalue_from_vector(
+                self.location,
+                vector_type,
+                &vec![element; element_count],
+            );
+            res = op(res, broadcast, self.context);
+        }
+        let zero = self.context.new_rvalue_zero(self.int_type);
+        self.extract_element(res, zero)
     }
 
+    #[cfg(feature = "master")]
     pub fn vector_reduce_op(&mut self, src: RValue<'gcc>, op: BinaryOp) -> RValue<'gcc> {
+        let vector_type = src.get_type().unqualified().dyncast_vector().expect("vector type");
+        if !vector_type.get_num_units().is_power_of_two() {
+            // NOTE: the operation is not idempotent, so vector_reduce() cannot be used.
+            return self.vector_reduce_op_linear(src, op);
+        }
         let loc = self.location;
         self.vector_reduce(src, |a, b, context| context.new_binary_op(loc, op, a.get_type(), a, b))
     }
 
-    pub fn vector_reduce_fadd_reassoc(
-        &mut self,
-        _acc: RValue<'gcc>,
-        _src: RValue<'gcc>,
-    ) -> RValue<'gcc> {
-        unimplemented!();
+    #[cfg(not(feature = "master"))]
+    pub fn vector_reduce_op(&mut self, src: RValue<'gcc>, op: BinaryOp) -> RValue<'gcc> {
+        // NOTE: without vector permutations, reduce the elements one after the other.
+        self.vector_reduce_op_linear(src, op)
     }
 
-    #[cfg(feature = "master")]
-    pub fn vector_reduce_fadd(&mut self, acc: RValue<'gcc>, src: RValue<'gcc>) -> RValue<'gcc> {
+    /// Reduce the elements of the vector one after the other.
+    fn vector_reduce_op_linear(&mut self, src: RValue<'gcc>, op: BinaryOp) -> RValue<'gcc> {
+        let mut elements = self.vector_elements(src).into_iter();
+        let first = elements.next().expect("empty vector");
+        elements.fold(first, |acc, element| {
+            self.context.new_binary_op(self.location, op, acc.get_type(), acc, element)
+        })
+    }
+
+    /// Returns the elements of the vector `src`, in order.
+    fn vector_elements(&mut self, src: RValue<'gcc>) -> Vec<RValue<'gcc>> {
         let vector_type = src.get_type().unqualified().dyncast_vector().expect("vector type");
         let element_count = vector_type.get_num_units();
         (0..element_count)
             .map(|i| {
-                self.context
-                    .new_vector_access(
-                        self.location,
-                        src,
-                        self.context.new_rvalue_from_int(self.int_type, i as _),
-                    )
-                    .to_rvalue()
+                let index = self.context.new_rvalue_from_int(self.int_type, i as _);
+                self.extract_element(src, index)
             })
-            .fold(acc, |x, i| x + i)
+            .collect()
     }
 
-    #[cfg(not(feature = "master"))]
-    pub fn vector_reduce_fadd(&mut self, _acc: RValue<'gcc>, _src: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    /// Reassociative reduction: the elements can be added in any order.
+    pub fn vector_reduce_fadd_reassoc(
+        &mut self,
+        acc: RValue<'gcc>,
+        src: RValue<'gcc>,
+    ) -> RValue<'gcc> {
+        let sum = self.vector_reduce_op(src, BinaryOp::Plus);
+        self.context.new_binary_op(self.location, BinaryOp::Plus, acc.get_type(), acc, sum)
+    }
+
+    /// Ordered reduction: the elements are added from first to last to get the same rounding as
+    /// a scalar loop.
+    pub fn vector_reduce_fadd(&mut self, acc: RValue<'gcc>, src: RValue<'gcc>) -> RValue<'gcc> {
+        self.vector_elements(src).into_iter().fold(acc, |x, i| x + i)
     }
 
+    /// Reassociative reduction: the elements can be multiplied in any order.
     pub fn vector_reduce_fmul_reassoc(
         &mut self,
-        _acc: RValue<'gcc>,
-        _src: RValue<'gcc>,
+        acc: RValue<'gcc>,
+        src: RValue<'gcc>,
     ) -> RValue<'gcc> {
-        unimplemented!();
+        let product = self.vector_reduce_op(src, BinaryOp::Mult);
+        self.context.new_binary_op(self.location, BinaryOp::Mult, acc.get_type(), acc, product)
     }
 
-    #[cfg(feature = "master")]
+    /// Ordered reduction: the elements are multiplied from first to last to get the same rounding
+    /// as a scalar loop.
     pub fn vector_reduce_fmul(&mut self, acc: RValue<'gcc>, src: RValue<'gcc>) -> RValue<'gcc> {
-        let vector_type = src.get_type().unqualified().dyncast_vector().expect("vector type");
-        let element_count = vector_type.get_num_units();
-        (0..element_count)
-            .map(|i| {
-                self.context
-                    .new_vector_access(
-                        self.location,
-                        src,
-                        self.context.new_rvalue_from_int(self.int_type, i as _),
-                    )
-                    .to_rvalue()
-            })
-            .fold(acc, |x, i| x * i)
-    }
-
-    #[cfg(not(feature = "master"))]
-    pub fn vector_reduce_fmul(&mut self, _acc: RValue<'gcc>, _src: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!()
+        self.vector_elements(src).into_iter().fold(acc, |x, i| x * i)
     }
 
     // Inspired by Hacker's Delight min implementation.
@@ -2185,65 +2276,34 @@ impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
         self.vector_extremum(a, b, ExtremumOperation::Min)
     }
 
-    #[cfg(feature = "master")]
     pub fn vector_reduce_fmin(&mut self, src: RValue<'gcc>) -> RValue<'gcc> {
-        let vector_type = src.get_type().unqualified().dyncast_vector().expect("vector type");
-        let element_count = vector_type.get_num_units();
-        let mut acc = self
-            .context
-            .new_vector_access(self.location, src, self.context.new_rvalue_zero(self.int_type))
-            .to_rvalue();
-        for i in 1..element_count {
-            let elem = self
-                .context
-                .new_vector_access(
-                    self.location,
-                    src,
-                    self.context.new_rvalue_from_int(self.int_type, i as _),
-                )
-                .to_rvalue();
-            let cmp = self.context.new_comparison(self.location, ComparisonOp::LessThan, acc, elem);
-            acc = self.select(cmp, acc, elem);
-        }
-        acc
-    }
-
-    #[cfg(not(feature = "master"))]
-    pub fn vector_reduce_fmin(&mut self, _src: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+        self.vector_reduce_extremum(src, ExtremumOperation::Min)
     }
 
     pub fn vector_fmax(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
         self.vector_extremum(a, b, ExtremumOperation::Max)
     }
 
-    #[cfg(feature = "master")]
     pub fn vector_reduce_fmax(&mut self, src: RValue<'gcc>) -> RValue<'gcc> {
-        let vector_type = src.get_type().unqualified().dyncast_vector().expect("vector type");
-        let element_count = vector_type.get_num_units();
-        let mut acc = self
-            .context
-            .new_vector_access(self.location, src, self.context.new_rvalue_zero(self.int_type))
-            .to_rvalue();
-        for i in 1..element_count {
-            let elem = self
-                .context
-                .new_vector_access(
-                    self.location,
-                    src,
-                    self.context.new_rvalue_from_int(self.int_type, i as _),
-                )
-                .to_rvalue();
-            let cmp =
-                self.context.new_comparison(self.location, ComparisonOp::GreaterThan, acc, elem);
-            acc = self.select(cmp, acc, elem);
-        }
-        acc
+        self.vector_reduce_extremum(src, ExtremumOperation::Max)
     }
 
-    #[cfg(not(feature = "master"))]
-    pub fn vector_reduce_fmax(&mut self, _src: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    fn vector_reduce_extremum(
+        &mut self,
+        src: RValue<'gcc>,
+        direction: ExtremumOperation,
+    ) -> RValue<'gcc> {
+        let mut elements = self.vector_elements(src).into_iter();
+        let mut acc = elements.next().expect("empty vector");
+        for element in elements {
+            let comparison_op = match direction {
+                ExtremumOperation::Min => ComparisonOp::LessThan,
+                ExtremumOperation::Max => ComparisonOp::GreaterThan,
+            };
+            let cmp = self.context.new_comparison(self.location, comparison_op, acc, element);
+            acc = self.select(cmp, acc, element);
+        }
+        acc
     }
 
     pub fn vector_select(
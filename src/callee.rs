@@ -3,9 +3,11 @@ use gccjit::{FnAttribute, Visibility};
 use gccjit::{Function, FunctionType};
 use rustc_middle::ty::layout::{FnAbiOf, HasTyCtxt};
 use rustc_middle::ty::{self, Instance, TypeVisitableExt};
+use rustc_span::sym;
 
-use crate::attributes;
 use crate::context::CodegenCx;
+use crate::errors::{UnsupportedLinkage, UnsupportedLlvmIntrinsic};
+use crate::{attributes, base};
 
 /// Codegens a reference to a fn/method item, monomorphizing and
 /// inlining as it goes.
@@ -67,13 +69,41 @@ pub fn get_fn<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, instance: Instance<'tcx>)
             func
         }*/
     } else {
+        cx.check_calling_convention(fn_abi, instance.def_id());
         cx.linkage.set(FunctionType::Extern);
         let func = cx.declare_fn(sym, fn_abi);
 
+        if sym.starts_with("llvm.") && !cx.intrinsics.borrow().contains_key(sym) {
+            let span = tcx
+                .get_attr(instance.def_id(), sym::link_name)
+                .map_or_else(|| tcx.def_span(instance.def_id()), |attr| attr.span);
+            tcx.dcx().emit_err(UnsupportedLlvmIntrinsic {
+                span,
+                name: sym,
+                arch: &tcx.sess.target.arch,
+            });
+        }
+
         attributes::from_fn_attrs(cx, func, instance);
 
         let instance_def_id = instance.def_id();
 
+        // NOTE: a weak import of a function that is not defined resolves to null, like for the
+        // statics in `check_and_apply_linkage()`.
+        if tcx.is_foreign_item(instance_def_id)
+            && let Some(linkage) = tcx.codegen_fn_attrs(instance_def_id).import_linkage
+        {
+            if base::linkage_to_gcc(linkage).is_none() {
+                tcx.dcx().emit_err(UnsupportedLinkage {
+                    span: tcx.def_span(instance_def_id),
+                    linkage: format!("{:?}", linkage),
+                });
+            } else if base::linkage_is_weak(linkage) {
+                #[cfg(feature = "master")]
+                func.add_attribute(FnAttribute::Weak);
+            }
+        }
+
         // TODO(antoyo): set linkage and attributes.
 
         // Apply an appropriate linkage/visibility value to our item that we
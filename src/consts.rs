@@ -7,16 +7,20 @@ use rustc_middle::middle::codegen_fn_attrs::{CodegenFnAttrFlags, CodegenFnAttrs}
 use rustc_middle::mir::interpret::{
     self, read_target_uint, ConstAllocation, ErrorHandled, Scalar as InterpScalar,
 };
-use rustc_middle::mir::mono::Linkage;
 use rustc_middle::ty::layout::LayoutOf;
 use rustc_middle::ty::{self, Instance};
 use rustc_middle::{bug, span_bug};
 use rustc_span::def_id::DefId;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::Span;
 use rustc_target::abi::{self, Align, HasDataLayout, Primitive, Size, WrappingRange};
 
 use crate::base;
 use crate::context::CodegenCx;
-use crate::errors::InvalidMinimumAlignment;
+use crate::errors::{
+    EmptyLinkSection, FunctionLinkSectionIgnored, InvalidMachOLinkSection, InvalidMinimumAlignment,
+    UnsupportedLinkage, UsedLinkerUnsupported,
+};
 use crate::type_of::LayoutGccExt;
 
 fn set_global_alignment<'gcc, 'tcx>(
@@ -142,35 +146,120 @@ impl<'gcc, 'tcx> StaticMethods for CodegenCx<'gcc, 'tcx> {
             }
         }
 
-        // Wasm statics with custom link sections get special treatment as they
-        // go into custom sections of the wasm executable.
-        if self.tcx.sess.opts.target_triple.triple().starts_with("wasm32") {
-            if let Some(_section) = attrs.link_section {
-                unimplemented!();
-            }
-        } else {
-            // TODO(antoyo): set link section.
+        // NOTE: the link section was set when the global was declared in predefine_static().
+
+        if attrs.flags.contains(CodegenFnAttrFlags::USED) {
+            // `USED` and `USED_LINKER` can't be used together.
+            assert!(!attrs.flags.contains(CodegenFnAttrFlags::USED_LINKER));
+
+            // The semantics of #[used] in Rust only require the symbol to make it into the
+            // object file, so it is allowed for the linker to strip the symbol if it is dead.
+            self.add_compiler_used_global(global.to_rvalue());
         }
+        if attrs.flags.contains(CodegenFnAttrFlags::USED_LINKER) {
+            // `USED` and `USED_LINKER` can't be used together.
+            assert!(!attrs.flags.contains(CodegenFnAttrFlags::USED));
 
-        if attrs.flags.contains(CodegenFnAttrFlags::USED)
-            || attrs.flags.contains(CodegenFnAttrFlags::USED_LINKER)
-        {
             self.add_used_global(global.to_rvalue());
+            let symbol_name = self.tcx.symbol_name(Instance::mono(self.tcx, def_id)).name;
+            self.retain_global(def_id, symbol_name);
         }
     }
 
     /// Add a global value to a list to be stored in the `llvm.used` variable, an array of i8*.
-    fn add_used_global(&self, _global: RValue<'gcc>) {
-        // TODO(antoyo)
+    fn add_used_global(&self, global: RValue<'gcc>) {
+        self.add_compiler_used_global(global);
+        // NOTE: keeping the global through the linker garbage collection requires its symbol
+        // name, so this is done by retain_global().
     }
 
-    fn add_compiler_used_global(&self, global: RValue<'gcc>) {
-        // NOTE: seems like GCC does not make the distinction between compiler.used and used.
-        self.add_used_global(global);
+    fn add_compiler_used_global(&self, _global: RValue<'gcc>) {
+        // TODO: use the used attribute when libgccjit supports it for variables.
+        // NOTE: there is nothing to do here: GCC never removes a global that is visible outside
+        // of the object file and the statics are always declared with GlobalKind::Exported by
+        // define_global(), so they are already kept by the compiler. Unlike `#[used(linker)]`,
+        // `#[used(compiler)]` does not need to survive the linker garbage collection, so the
+        // globals do not need to be retained with retain_global().
     }
 }
 
+pub(crate) fn retain_asm(symbol_name: &str) -> String {
+    format!(
+        ".pushsection .rustc_retain,\"R\"\n.reloc ., BFD_RELOC_NONE, {}\n.popsection",
+        symbol_name
+    )
+}
+
 impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
+    /// Check that the `#[link_section]` of an item is valid for the target and report an error if
+    /// it is not.
+    pub fn check_link_section(&self, def_id: DefId, section: Symbol) -> bool {
+        let span = || self.link_section_span(def_id);
+        let section = section.as_str();
+        if section.is_empty() {
+            self.tcx.dcx().emit_err(EmptyLinkSection { span: span() });
+            return false;
+        }
+        if self.tcx.sess.target.is_like_osx {
+            let mut parts = section.split(',');
+            let segment = parts.next().unwrap_or_default();
+            let section_name = parts.next().unwrap_or_default();
+            let is_valid_name = |name: &str| !name.is_empty() && name.len() <= 16;
+            if !is_valid_name(segment) || !is_valid_name(section_name) {
+                self.tcx.dcx().emit_err(InvalidMachOLinkSection {
+                    span: span(),
+                    section: section.to_string(),
+                });
+                return false;
+            }
+        }
+        true
+    }
+
+    fn link_section_span(&self, def_id: DefId) -> Span {
+        self.tcx
+            .get_attr(def_id, sym::link_section)
+            .map_or_else(|| self.tcx.def_span(def_id), |attr| attr.span)
+    }
+
+    /// libgccjit cannot set the section of a function, so on ELF targets, the function is compiled
+    /// in its own section, which is renamed once the object file is compiled: see
+    /// `back::write::set_function_link_sections()`.
+    pub fn set_function_link_section(&self, def_id: DefId, symbol_name: &str, section: Symbol) {
+        if !self.is_elf_target() {
+            // TODO: set the section attribute when libgccjit supports it.
+            let span = self.link_section_span(def_id);
+            self.tcx.dcx().emit_warn(FunctionLinkSectionIgnored { span });
+            return;
+        }
+
+        let mut function_link_sections = self.function_link_sections.borrow_mut();
+        if function_link_sections.is_empty() {
+            self.context.add_command_line_option("-ffunction-sections");
+        }
+        function_link_sections.push((symbol_name.to_string(), section.to_string()));
+    }
+
+    /// Prevent the linker from garbage collecting the section of the global, which is the
+    /// equivalent of `__attribute__((retain))`.
+    pub fn retain_global(&self, def_id: DefId, symbol_name: &str) {
+        if !self.is_elf_target() {
+            // NOTE: the SHF_GNU_RETAIN flag only exists for ELF.
+            return;
+        }
+        if !self.target_info.supports_retain() {
+            let span = self.tcx.def_span(def_id);
+            self.tcx.dcx().emit_warn(UsedLinkerUnsupported { span });
+            return;
+        }
+        // TODO: use the retain attribute when libgccjit supports it.
+        // NOTE: a section with the SHF_GNU_RETAIN flag ("R") is never garbage collected by the
+        // linker, so the relocation from this section keeps the section of the global as well.
+        // The section is empty and not allocated, so the cost is a single R_*_NONE relocation in
+        // the object file: nothing remains in the linked binary.
+        self.context.add_top_level_asm(None, &retain_asm(symbol_name));
+    }
+
     #[cfg_attr(not(feature = "master"), allow(unused_variables))]
     pub fn add_used_function(&self, function: Function<'gcc>) {
         #[cfg(feature = "master")]
@@ -262,7 +351,7 @@ impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
 
             global
         } else {
-            check_and_apply_linkage(self, fn_attrs, gcc_type, sym)
+            check_and_apply_linkage(self, fn_attrs, gcc_type, sym, def_id)
         };
 
         if !def_id.is_local() {
@@ -378,14 +467,21 @@ fn check_and_apply_linkage<'gcc, 'tcx>(
     attrs: &CodegenFnAttrs,
     gcc_type: Type<'gcc>,
     sym: &str,
+    def_id: DefId,
 ) -> LValue<'gcc> {
     let is_tls = attrs.flags.contains(CodegenFnAttrFlags::THREAD_LOCAL);
     if let Some(linkage) = attrs.import_linkage {
+        let global_kind = base::global_linkage_to_gcc(linkage).unwrap_or_else(|| {
+            cx.tcx.dcx().emit_err(UnsupportedLinkage {
+                span: cx.tcx.def_span(def_id),
+                linkage: format!("{:?}", linkage),
+            });
+            GlobalKind::Imported
+        });
         // Declare a symbol `foo` with the desired linkage.
-        let global1 =
-            cx.declare_global_with_linkage(sym, cx.type_i8(), base::global_linkage_to_gcc(linkage));
+        let global1 = cx.declare_global_with_linkage(sym, cx.type_i8(), global_kind);
 
-        if linkage == Linkage::ExternalWeak {
+        if base::linkage_is_weak(linkage) {
             #[cfg(feature = "master")]
             global1.add_attribute(VarAttribute::Weak);
         }
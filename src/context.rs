@@ -24,6 +24,9 @@ use rustc_target::spec::{HasTargetSpec, HasWasmCAbiOpt, Target, TlsModel, WasmCA
 
 use crate::callee::get_fn;
 use crate::common::SignType;
+#[cfg(not(feature = "master"))]
+use crate::common::TypeReflection;
+use crate::{gcc_util, LockedTargetInfo};
 
 #[cfg_attr(not(feature = "master"), allow(dead_code))]
 pub struct CodegenCx<'gcc, 'tcx> {
@@ -72,10 +75,14 @@ pub struct CodegenCx<'gcc, 'tcx> {
     pub supports_f32_type: bool,
     pub supports_f64_type: bool,
     pub supports_f128_type: bool,
+    pub target_info: LockedTargetInfo,
 
     pub float_type: Type<'gcc>,
     pub double_type: Type<'gcc>,
 
+    /// Whether floating-point values are passed in general purpose registers.
+    pub soft_float: bool,
+
     pub linkage: Cell<FunctionType>,
     pub scalar_types: RefCell<FxHashMap<Ty<'tcx>, Type<'gcc>>>,
     pub types: RefCell<FxHashMap<(Ty<'tcx>, Option<VariantIdx>), Type<'gcc>>>,
@@ -87,6 +94,9 @@ pub struct CodegenCx<'gcc, 'tcx> {
     pub instances: RefCell<FxHashMap<Instance<'tcx>, LValue<'gcc>>>,
     /// Cache function instances of monomorphic and polymorphic items
     pub function_instances: RefCell<FxHashMap<Instance<'tcx>, Function<'gcc>>>,
+    /// The functions with a `#[link_section]` (symbol name, section name): see
+    /// `back::write::set_function_link_sections()`.
+    pub function_link_sections: RefCell<Vec<(String, String)>>,
     /// Cache generated vtables
     pub vtables:
         RefCell<FxHashMap<(Ty<'tcx>, Option<ty::PolyExistentialTraitRef<'tcx>>), RValue<'gcc>>>,
@@ -96,6 +106,10 @@ pub struct CodegenCx<'gcc, 'tcx> {
     pub on_stack_params: RefCell<FxHashMap<FunctionPtrType<'gcc>, FxHashSet<usize>>>,
     /// Mapping from function to indexes of on stack parameters.
     pub on_stack_function_params: RefCell<FxHashMap<Function<'gcc>, FxHashSet<usize>>>,
+    /// Mapping from function to the floating-point types of the parameters that are passed as
+    /// integers because of soft-float.
+    pub soft_float_function_params:
+        RefCell<FxHashMap<Function<'gcc>, FxHashMap<usize, Type<'gcc>>>>,
 
     /// Cache of emitted const globals (value -> global)
     pub const_globals: RefCell<FxHashMap<RValue<'gcc>, RValue<'gcc>>>,
@@ -141,6 +155,7 @@ impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
         supports_f32_type: bool,
         supports_f64_type: bool,
         supports_f128_type: bool,
+        target_info: LockedTargetInfo,
     ) -> Self {
         let create_type = |ctype, rust_type| {
             let layout = tcx.layout_of(ParamEnv::reveal_all().and(rust_type)).unwrap();
@@ -283,15 +298,20 @@ impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
             supports_f32_type,
             supports_f64_type,
             supports_f128_type,
+            target_info,
 
             float_type,
             double_type,
 
+            soft_float: gcc_util::has_soft_float(tcx.sess),
+
             linkage: Cell::new(FunctionType::Internal),
             instances: Default::default(),
             function_instances: Default::default(),
+            function_link_sections: Default::default(),
             on_stack_params: Default::default(),
             on_stack_function_params: Default::default(),
+            soft_float_function_params: Default::default(),
             vtables: Default::default(),
             const_globals: Default::default(),
             global_lvalues: Default::default(),
@@ -363,10 +383,36 @@ impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
             || typ.is_compatible_with(self.bool_type)
     }
 
+    pub fn is_float_type(&self, typ: Type<'gcc>) -> bool {
+        #[cfg(feature = "master")]
+        {
+            typ.is_floating_point()
+        }
+        // NOTE: is_floating_point() requires the master branch of libgccjit, so the floating-point
+        // types, including f16 and f128, are found by excluding every other kind of type.
+        #[cfg(not(feature = "master"))]
+        {
+            !self.is_int_type_or_bool(typ)
+                && typ.get_pointee().is_none()
+                && !typ.is_vector()
+                && typ.dyncast_array().is_none()
+                && typ.is_struct().is_none()
+                && typ.dyncast_function_ptr_type().is_none()
+                && typ != self.type_void()
+        }
+    }
+
     pub fn sess(&self) -> &'tcx Session {
         self.tcx.sess
     }
 
+    /// Whether the object files of the target are ELF files, which is required to set the
+    /// section of a function or to retain a global.
+    pub fn is_elf_target(&self) -> bool {
+        let target = &self.tcx.sess.target;
+        !(target.is_like_osx || target.is_like_windows || target.is_like_aix || target.is_like_wasm)
+    }
+
     pub fn bitcast_if_needed(
         &self,
         value: RValue<'gcc>,
@@ -494,7 +540,8 @@ impl<'gcc, 'tcx> MiscMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
     }
 
     fn set_frame_pointer_type(&self, _llfn: RValue<'gcc>) {
-        // TODO(antoyo)
+        // NOTE: the frame pointer policy is the same for every function, so it is set with
+        // command-line options in new_context().
     }
 
     fn apply_target_cpu_attr(&self, _llfn: RValue<'gcc>) {
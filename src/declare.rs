@@ -120,6 +120,7 @@ impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
             arguments_type,
             is_c_variadic,
             on_stack_param_indices,
+            soft_float_param_types,
             #[cfg(feature = "master")]
             fn_attributes,
         } = fn_abi.gcc_type(self);
@@ -132,6 +133,9 @@ impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
             is_c_variadic,
         );
         self.on_stack_function_params.borrow_mut().insert(func, on_stack_param_indices);
+        if !soft_float_param_types.is_empty() {
+            self.soft_float_function_params.borrow_mut().insert(func, soft_float_param_types);
+        }
         #[cfg(feature = "master")]
         for fn_attr in fn_attributes {
             func.add_attribute(fn_attr);
@@ -174,7 +178,15 @@ fn declare_raw_fn<'gcc>(
                 // here.
                 cx.context.get_builtin_function("fma")
             }
-            _ => llvm::intrinsic(name, cx),
+            _ => match llvm::intrinsic(name, cx) {
+                Some(intrinsic) => intrinsic,
+                None => {
+                    // NOTE: the intrinsic is not recorded in cx.intrinsics so that get_fn() can
+                    // report the error with the span of the declaration. The builtin is only a
+                    // placeholder to keep going and report the other errors.
+                    return cx.context.get_builtin_function("__builtin_trap");
+                }
+            },
         };
 
         cx.intrinsics.borrow_mut().insert(name.to_string(), intrinsic);
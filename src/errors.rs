@@ -1,6 +1,6 @@
 use rustc_errors::{Diag, DiagCtxtHandle, Diagnostic, EmissionGuarantee, Level};
 use rustc_macros::{Diagnostic, Subdiagnostic};
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 
 use crate::fluent_generated as fluent;
 
@@ -32,6 +32,152 @@ pub(crate) enum PossibleFeature<'a> {
 #[diag(codegen_gcc_lto_not_supported)]
 pub(crate) struct LTONotSupported;
 
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_unsupported_sanitizer)]
+pub(crate) struct UnsupportedSanitizer<'a> {
+    pub sanitizer: &'a str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_no_sanitize_unsupported)]
+#[note]
+pub(crate) struct NoSanitizeUnsupported {
+    #[primary_span]
+    pub span: Span,
+    pub sanitizers: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_empty_link_section)]
+pub(crate) struct EmptyLinkSection {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_invalid_mach_o_link_section)]
+#[help]
+pub(crate) struct InvalidMachOLinkSection {
+    #[primary_span]
+    pub span: Span,
+    pub section: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_function_link_section_ignored)]
+#[note]
+pub(crate) struct FunctionLinkSectionIgnored {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_used_linker_unsupported)]
+#[note]
+pub(crate) struct UsedLinkerUnsupported {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_function_link_section_not_found)]
+#[note]
+pub(crate) struct FunctionLinkSectionNotFound<'a> {
+    pub symbol: &'a str,
+    pub path: &'a str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_set_function_link_sections)]
+pub(crate) struct SetFunctionLinkSections<'a> {
+    pub path: &'a str,
+    pub err: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_naked_function_unsupported_target)]
+pub(crate) struct NakedFunctionUnsupportedTarget {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_naked_function_unsupported_linkage)]
+pub(crate) struct NakedFunctionUnsupportedLinkage {
+    #[primary_span]
+    pub span: Span,
+    pub linkage: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_unsupported_linkage)]
+pub(crate) struct UnsupportedLinkage {
+    #[primary_span]
+    pub span: Span,
+    pub linkage: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_unsupported_calling_convention)]
+#[note]
+pub(crate) struct UnsupportedCallingConvention {
+    #[primary_span]
+    pub span: Span,
+    pub conv: String,
+    pub attribute: &'static str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_c_variadic_definition_unsupported)]
+#[note]
+pub(crate) struct CVariadicDefinitionUnsupported {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_va_arg_unsupported)]
+#[note]
+pub(crate) struct VaArgUnsupported {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_non_constant_amx_tile)]
+#[note]
+pub(crate) struct NonConstantAmxTile<'a> {
+    pub name: &'a str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_unsupported_llvm_intrinsic)]
+#[note]
+pub(crate) struct UnsupportedLlvmIntrinsic<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub name: &'a str,
+    pub arch: &'a str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_unsupported_simd_intrinsic)]
+pub(crate) struct UnsupportedSimdIntrinsic<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub name: Symbol,
+    pub arch: &'a str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_unsupported_inline_asm_register)]
+pub(crate) struct UnsupportedInlineAsmRegister<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub register: &'a str,
+    pub arch: &'a str,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_gcc_unwinding_inline_asm)]
 pub(crate) struct UnwindingInlineAsm {
@@ -3,6 +3,7 @@ use gccjit::Context;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_middle::bug;
 use rustc_session::Session;
+use rustc_target::spec::SanitizerSet;
 use rustc_target::target_features::RUSTC_SPECIFIC_FEATURES;
 use smallvec::{smallvec, SmallVec};
 
@@ -128,6 +129,20 @@ pub(crate) fn global_gcc_features(sess: &Session, diagnostics: bool) -> Vec<Stri
     features
 }
 
+/// Returns whether `feature` is enabled in a list of features as returned by
+/// [`global_gcc_features`]. Features that come later override the ones that come earlier.
+pub fn is_feature_enabled(features: &[String], feature: &str) -> bool {
+    features
+        .iter()
+        .rev()
+        .find_map(|feat| match feat.strip_prefix(&['+', '-'][..]) {
+            Some(name) if name == feature => Some(feat.starts_with('+')),
+            Some(_) => None,
+            None => (feat == feature).then_some(true),
+        })
+        .unwrap_or(false)
+}
+
 /// Returns a feature name for the given `+feature` or `-feature` string.
 ///
 /// Only allows features that are backend specific (i.e. not [`RUSTC_SPECIFIC_FEATURES`].)
@@ -185,6 +200,111 @@ pub fn to_gcc_features<'a>(sess: &Session, s: &'a str) -> SmallVec<[&'a str; 2]>
     }
 }
 
+/// The aarch64 extensions that can be enabled or disabled with `+ext` and `+noext` in GCC's
+/// `target` attribute. The other features (e.g. `v8.1a` or `bti`) can only be set for the whole
+/// crate.
+const AARCH64_GCC_EXTENSIONS: &[&str] = &[
+    "aes",
+    "bf16",
+    "crc",
+    "crypto",
+    "dotprod",
+    "f32mm",
+    "f64mm",
+    "flagm",
+    "fp",
+    "fp16",
+    "fp16fml",
+    "i8mm",
+    "lse",
+    "memtag",
+    "pauth",
+    "rcpc",
+    "rdma",
+    "rng",
+    "sb",
+    "sha2",
+    "sha3",
+    "simd",
+    "sm4",
+    "ssbs",
+    "sve",
+    "sve2",
+    "sve2-aes",
+    "sve2-bitperm",
+    "sve2-sha3",
+    "sve2-sm4",
+    "tme",
+];
+
+/// Returns the name of the aarch64 extension in GCC's `target` attribute for a feature as returned
+/// by [`to_gcc_features`], which uses the LLVM names.
+fn aarch64_gcc_extension(feature: &str) -> Option<&str> {
+    let extension = match feature {
+        "neon" => "simd",
+        "fp-armv8" => "fp",
+        "fullfp16" => "fp16",
+        "rdm" => "rdma",
+        "rand" => "rng",
+        "mte" => "memtag",
+        _ => feature,
+    };
+    AARCH64_GCC_EXTENSIONS.contains(&extension).then_some(extension)
+}
+
+/// Returns the value of GCC's `target` attribute enabling the provided GCC features, using the
+/// syntax of the current architecture. Features prefixed by `-` are disabled, the others are
+/// enabled. Features that cannot be set on a single function are ignored.
+pub fn to_gcc_target_attribute(sess: &Session, features: &[&str]) -> String {
+    let features = features.iter().map(|feature| match feature.strip_prefix('-') {
+        Some(name) => (false, name),
+        None => (true, feature.strip_prefix('+').unwrap_or(feature)),
+    });
+    match &*sess.target.arch {
+        "x86" | "x86_64" | "powerpc" => features
+            .map(|(enable, name)| if enable { name.to_string() } else { format!("no-{}", name) })
+            .collect::<Vec<_>>()
+            .join(","),
+        "aarch64" => features
+            .filter_map(|(enable, name)| match name {
+                "strict-align" => {
+                    Some(if enable { "strict-align" } else { "no-strict-align" }.to_string())
+                }
+                _ => aarch64_gcc_extension(name).map(|extension| {
+                    if enable { format!("+{}", extension) } else { format!("+no{}", extension) }
+                }),
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        // NOTE: on arm, GCC only allows changing the instruction set and the FPU, the other
+        // extensions can only be enabled with `arch=`, which would override `-march`.
+        "arm" => features
+            .filter_map(|(enable, name)| match (enable, name) {
+                (true, "thumb-mode") => Some("thumb"),
+                (false, "thumb-mode") => Some("arm"),
+                (true, "neon") => Some("fpu=neon"),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        // NOTE: on RISC-V, GCC only allows adding extensions to the current ISA.
+        "riscv32" | "riscv64" => {
+            let extensions = features
+                .filter(|&(enable, name)| {
+                    enable && name != "e" && (name.len() == 1 || name.starts_with('z'))
+                })
+                .map(|(_, name)| format!("+{}", name))
+                .collect::<Vec<_>>();
+            if extensions.is_empty() {
+                String::new()
+            } else {
+                format!("arch={}", extensions.join(","))
+            }
+        }
+        _ => String::new(),
+    }
+}
+
 // Given a map from target_features to whether they are enabled or disabled,
 // ensure only valid combinations are allowed.
 pub fn check_tied_features(
@@ -202,6 +322,27 @@ pub fn check_tied_features(
     None
 }
 
+/// Returns whether floating-point values must not be stored in the floating-point registers,
+/// either because the target uses a soft-float ABI or because of `-Ctarget-feature=+soft-float`.
+pub fn has_soft_float(sess: &Session) -> bool {
+    sess.target.abi == "softfloat"
+        || is_feature_enabled(&global_gcc_features(sess, false), "soft-float")
+}
+
+/// Returns the name of the sanitizer as expected by GCC's `-fsanitize=` option or `None` if GCC
+/// doesn't support it.
+pub fn sanitizer_to_gcc(sanitizer: SanitizerSet) -> Option<&'static str> {
+    match sanitizer {
+        SanitizerSet::ADDRESS => Some("address"),
+        SanitizerSet::KERNELADDRESS => Some("kernel-address"),
+        SanitizerSet::HWADDRESS => Some("hwaddress"),
+        SanitizerSet::LEAK => Some("leak"),
+        SanitizerSet::THREAD => Some("thread"),
+        SanitizerSet::SHADOWCALLSTACK => Some("shadow-call-stack"),
+        _ => None,
+    }
+}
+
 fn arch_to_gcc(name: &str) -> &str {
     match name {
         "M68020" => "68020",
@@ -1,11 +1,12 @@
 use std::borrow::Cow;
 
 use gccjit::{CType, Context};
-use gccjit::{Function, FunctionPtrType, RValue, ToRValue, UnaryOp};
+use gccjit::{Function, FunctionPtrType, RValue, ToRValue, Type, UnaryOp};
 use rustc_codegen_ssa::traits::BuilderMethods;
 
 use crate::builder::Builder;
 use crate::context::CodegenCx;
+use crate::errors::NonConstantAmxTile;
 
 #[cfg_attr(not(feature = "master"), allow(unused_variables))]
 pub fn adjust_function<'gcc>(
@@ -39,6 +40,119 @@ pub fn adjust_function<'gcc>(
     func_ptr
 }
 
+/// Returns the name of the tile register whose number is the asm input operand `operand`, to be
+/// used in an asm template.
+fn tile_register(operand: usize) -> String {
+    // NOTE: the `c` modifier prints the constant without the immediate prefix.
+    format!("%%tmm%c{}", operand)
+}
+
+/// Whether `value` is an integer constant.
+/// libgccjit has no API for this, so this looks at the debug string, which is the integer,
+/// possibly preceded by casts and followed by the `L` suffix of `long`, for an integer constant.
+fn is_integer_constant(value: RValue<'_>) -> bool {
+    let debug_string = format!("{:?}", value);
+    let mut value = debug_string.as_str();
+    while let Some(cast) = value.strip_prefix('(') {
+        let Some((_, casted_value)) = cast.split_once(')') else {
+            return false;
+        };
+        value = casted_value;
+    }
+    value.strip_suffix('L').unwrap_or(value).parse::<i64>().is_ok()
+}
+
+/// Emits the AMX tile intrinsics as inline asm, like GCC's `amxtileintrin.h` does.
+/// Returns `false` if `original_function_name` is not one of those intrinsics.
+pub fn amx_tile_intrinsic<'a, 'gcc, 'tcx>(
+    builder: &Builder<'a, 'gcc, 'tcx>,
+    original_function_name: &str,
+    args: &[RValue<'gcc>],
+) -> bool {
+    // NOTE: the templates contain both the AT&T and the Intel syntaxes since the dialect depends
+    // on the -masm option.
+    // The memory operands, when there are some, come first and are followed by the tile numbers,
+    // which are the first arguments of the intrinsics.
+    let (template, memory_operands, tile_count) = match original_function_name {
+        "llvm.x86.tileloadd64" | "llvm.x86.tileloaddt164" => {
+            let instruction = if original_function_name == "llvm.x86.tileloadd64" {
+                "tileloadd"
+            } else {
+                "tileloaddt1"
+            };
+            let tile = tile_register(2);
+            (
+                format!(
+                    "{{{instruction}\t(%0,%1,1), {tile}|{instruction}\t{tile}, [%0+%1*1]}}",
+                    instruction = instruction,
+                    tile = tile
+                ),
+                true,
+                1,
+            )
+        }
+        "llvm.x86.tilestored64" => {
+            let tile = tile_register(2);
+            (
+                format!(
+                    "{{tilestored\t{tile}, (%0,%1,1)|tilestored\t[%0+%1*1], {tile}}}",
+                    tile = tile
+                ),
+                true,
+                1,
+            )
+        }
+        "llvm.x86.tilezero" => (format!("tilezero\t{}", tile_register(0)), false, 1),
+        "llvm.x86.tilerelease" => ("tilerelease".to_string(), false, 0),
+        "llvm.x86.tdpbf16ps"
+        | "llvm.x86.tdpbssd"
+        | "llvm.x86.tdpbsud"
+        | "llvm.x86.tdpbusd"
+        | "llvm.x86.tdpbuud"
+        | "llvm.x86.tdpfp16ps"
+        | "llvm.x86.tcmmimfp16ps"
+        | "llvm.x86.tcmmrlfp16ps" => {
+            let instruction = original_function_name.trim_start_matches("llvm.x86.");
+            let dst = tile_register(0);
+            let a = tile_register(1);
+            let b = tile_register(2);
+            (
+                format!(
+                    "{{{instruction}\t{b}, {a}, {dst}|{instruction}\t{dst}, {a}, {b}}}",
+                    instruction = instruction,
+                    dst = dst,
+                    a = a,
+                    b = b
+                ),
+                false,
+                3,
+            )
+        }
+        _ => return false,
+    };
+
+    // NOTE: the `n` constraint below would make the compilation of the whole module fail without
+    // pointing to the intrinsic if a tile number is not a constant, so report it here instead.
+    if !args[..tile_count].iter().all(|&tile| is_integer_constant(tile)) {
+        builder.tcx.dcx().emit_err(NonConstantAmxTile { name: original_function_name });
+        return true;
+    }
+
+    let extended_asm = builder.llbb().add_extended_asm(None, &template);
+    extended_asm.set_volatile_flag(true);
+    if memory_operands {
+        let base = builder.context.new_cast(None, args[1], builder.usize_type);
+        let stride = builder.context.new_cast(None, args[2], builder.usize_type);
+        extended_asm.add_input_operand(None, "r", base);
+        extended_asm.add_input_operand(None, "r", stride);
+        extended_asm.add_clobber("memory");
+    }
+    for &tile in &args[..tile_count] {
+        extended_asm.add_input_operand(None, "n", tile);
+    }
+    true
+}
+
 pub fn adjust_intrinsic_arguments<'a, 'b, 'gcc, 'tcx>(
     builder: &Builder<'a, 'gcc, 'tcx>,
     gcc_func: FunctionPtrType<'gcc>,
@@ -46,7 +160,8 @@ pub fn adjust_intrinsic_arguments<'a, 'b, 'gcc, 'tcx>(
     func_name: &str,
     original_function_name: Option<&String>,
 ) -> Cow<'b, [RValue<'gcc>]> {
-    // TODO: this might not be a good way to workaround the missing tile builtins.
+    // NOTE: the unsupported LLVM intrinsics are mapped to __builtin_trap, which takes no
+    // arguments.
     if func_name == "__builtin_trap" {
         return vec![].into();
     }
@@ -662,6 +777,7 @@ pub fn adjust_intrinsic_return_value<'a, 'gcc, 'tcx>(
     args: &[RValue<'gcc>],
     args_adjusted: bool,
     orig_args: &[RValue<'gcc>],
+    return_type: Type<'gcc>,
 ) -> RValue<'gcc> {
     match func_name {
         "__builtin_ia32_vfmaddss3_round"
@@ -732,7 +848,33 @@ pub fn adjust_intrinsic_return_value<'a, 'gcc, 'tcx>(
         _ => (),
     }
 
-    return_value
+    cast_to_declared_return_type(builder, return_value, return_type)
+}
+
+/// Converts the value returned by a GCC builtin to the return type of the LLVM intrinsic it
+/// replaces when they differ, e.g. when the integer widths or the element types of the vectors
+/// differ.
+fn cast_to_declared_return_type<'a, 'gcc, 'tcx>(
+    builder: &Builder<'a, 'gcc, 'tcx>,
+    return_value: RValue<'gcc>,
+    return_type: Type<'gcc>,
+) -> RValue<'gcc> {
+    let value_type = return_value.get_type();
+    if value_type.is_compatible_with(return_type) {
+        return return_value;
+    }
+
+    let is_scalar = |typ| builder.is_int_type_or_bool(typ) || builder.is_float_type(typ);
+    match (value_type.dyncast_vector(), return_type.dyncast_vector()) {
+        (Some(_), Some(_)) if value_type.get_size() == return_type.get_size() => {
+            builder.context.new_bitcast(None, return_value, return_type)
+        }
+        (None, None) if is_scalar(value_type) && is_scalar(return_type) => {
+            builder.context.new_cast(None, return_value, return_type)
+        }
+        // NOTE: the other cases, like the structs built above, are already in the expected layout.
+        _ => return_value,
+    }
 }
 
 pub fn ignore_arg_cast(func_name: &str, index: usize, args_len: usize) -> bool {
@@ -794,7 +936,7 @@ pub fn ignore_arg_cast(func_name: &str, index: usize, args_len: usize) -> bool {
 }
 
 #[cfg(not(feature = "master"))]
-pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function<'gcc> {
+pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Option<Function<'gcc>> {
     let gcc_name = match name {
         "llvm.x86.sse2.pause" => {
             // NOTE: pause is only a hint, so we use a dummy built-in because target built-ins
@@ -802,21 +944,178 @@ pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function
             "__builtin_inff"
         }
         "llvm.x86.xgetbv" => "__builtin_trap",
-        _ => unimplemented!("unsupported LLVM intrinsic {}", name),
+        // NOTE: the tile intrinsics are emitted as inline asm in amx_tile_intrinsic(), so they
+        // do not need the target builtins.
+        "llvm.x86.tileloadd64"
+        | "llvm.x86.tilerelease"
+        | "llvm.x86.tilestored64"
+        | "llvm.x86.tileloaddt164"
+        | "llvm.x86.tilezero"
+        | "llvm.x86.tdpbf16ps"
+        | "llvm.x86.tdpbssd"
+        | "llvm.x86.tdpbsud"
+        | "llvm.x86.tdpbusd"
+        | "llvm.x86.tdpbuud"
+        | "llvm.x86.tdpfp16ps"
+        | "llvm.x86.tcmmimfp16ps"
+        | "llvm.x86.tcmmrlfp16ps" => "__builtin_trap",
+        _ => return None,
     };
     let func = cx.context.get_builtin_function(gcc_name);
     cx.functions.borrow_mut().insert(gcc_name.to_string(), func);
-    return func;
+    Some(func)
+}
+
+/// Converts the type suffix of an LLVM intrinsic to the machine mode used in the names of the
+/// GCC builtins.
+// NOTE: the v1i64 and v1f64 types are not mapped since the GCC builtins take scalars for them.
+#[cfg(feature = "master")]
+fn llvm_type_to_gcc_mode(typ: &str) -> Option<&'static str> {
+    let mode = match typ {
+        "i8" => "qi",
+        "i16" => "hi",
+        "i32" => "si",
+        "i64" => "di",
+        "f16" => "hf",
+        "f32" => "sf",
+        "f64" => "df",
+        "v8i8" => "v8qi",
+        "v16i8" => "v16qi",
+        "v4i16" => "v4hi",
+        "v8i16" => "v8hi",
+        "v2i32" => "v2si",
+        "v4i32" => "v4si",
+        "v2i64" => "v2di",
+        "v4f16" => "v4hf",
+        "v8f16" => "v8hf",
+        "v2f32" => "v2sf",
+        "v4f32" => "v4sf",
+        "v2f64" => "v2df",
+        _ => return None,
+    };
+    Some(mode)
+}
+
+// NOTE: the machine modes for which GCC defines the builtins, named after the mode iterators of
+// `aarch64-simd-builtins.def`.
+#[cfg(feature = "master")]
+const VDQ_BHSI: &[&str] = &["v8qi", "v16qi", "v4hi", "v8hi", "v2si", "v4si"];
+#[cfg(feature = "master")]
+const VDQ_I: &[&str] = &["v8qi", "v16qi", "v4hi", "v8hi", "v2si", "v4si", "v2di"];
+#[cfg(feature = "master")]
+const VSDQ_I_DI: &[&str] = &["v8qi", "v16qi", "v4hi", "v8hi", "v2si", "v4si", "v2di", "di"];
+#[cfg(feature = "master")]
+const VSDQ_I: &[&str] =
+    &["v8qi", "v16qi", "v4hi", "v8hi", "v2si", "v4si", "v2di", "qi", "hi", "si", "di"];
+#[cfg(feature = "master")]
+const VSDQ_HSI: &[&str] = &["v4hi", "v8hi", "v2si", "v4si", "hi", "si"];
+#[cfg(feature = "master")]
+const VHSDF: &[&str] = &["v4hf", "v8hf", "v2sf", "v4sf", "v2df"];
+#[cfg(feature = "master")]
+const VHSDF_DF: &[&str] = &["v4hf", "v8hf", "v2sf", "v4sf", "v2df", "df"];
+#[cfg(feature = "master")]
+const VHSDF_HSDF: &[&str] = &["v4hf", "v8hf", "v2sf", "v4sf", "v2df", "hf", "sf", "df"];
+
+/// Maps the `llvm.aarch64.neon.*` intrinsics to the `__builtin_aarch64_*` builtins used by GCC's
+/// `arm_neon.h`.
+/// The names of the builtins are made of the operation, the machine mode of the operands and,
+/// for the builtins working on unsigned values, a suffix giving the signedness of the result and
+/// of each argument (e.g. `_uus`).
+/// Returns `None` when GCC has no builtin for the operation in this mode, so that the intrinsic
+/// is reported as unsupported.
+// TODO: support the loads, stores, lane and table lookup intrinsics.
+#[cfg(feature = "master")]
+fn aarch64_neon_builtin(name: &str) -> Option<String> {
+    let mut parts = name.strip_prefix("llvm.aarch64.neon.")?.split('.');
+    let operation = parts.next()?;
+    // NOTE: for the reductions, the last type is the type of the vector argument.
+    let mode = llvm_type_to_gcc_mode(parts.last()?)?;
+    let is_float = mode.ends_with("hf") || mode.ends_with("sf") || mode.ends_with("df");
+
+    let (builtin, suffix, modes) = match operation {
+        "abs" => ("abs", "", VSDQ_I_DI),
+        "sqabs" => ("sqabs", "", VSDQ_I),
+        "sqneg" => ("sqneg", "", VSDQ_I),
+        "sqadd" => ("sqadd", "", VSDQ_I),
+        "uqadd" => ("uqadd", "_uuu", VSDQ_I),
+        "sqsub" => ("sqsub", "", VSDQ_I),
+        "uqsub" => ("uqsub", "_uuu", VSDQ_I),
+        "suqadd" => ("suqadd", "_ssu", VSDQ_I),
+        "usqadd" => ("usqadd", "_uus", VSDQ_I),
+        "shadd" => ("shadd", "", VDQ_BHSI),
+        "uhadd" => ("uhadd", "_uuu", VDQ_BHSI),
+        "srhadd" => ("srhadd", "", VDQ_BHSI),
+        "urhadd" => ("urhadd", "_uuu", VDQ_BHSI),
+        "shsub" => ("shsub", "", VDQ_BHSI),
+        "uhsub" => ("uhsub", "_uuu", VDQ_BHSI),
+        "sabd" => ("sabd", "", VDQ_BHSI),
+        "uabd" => ("uabd", "_uuu", VDQ_BHSI),
+        "smax" => ("smax", "", VDQ_BHSI),
+        "umax" => ("umax", "_uuu", VDQ_BHSI),
+        "smin" => ("smin", "", VDQ_BHSI),
+        "umin" => ("umin", "_uuu", VDQ_BHSI),
+        "smaxp" => ("smaxp", "", VDQ_BHSI),
+        "umaxp" => ("umaxp", "_uuu", VDQ_BHSI),
+        "sminp" => ("sminp", "", VDQ_BHSI),
+        "uminp" => ("uminp", "_uuu", VDQ_BHSI),
+        "addp" if is_float => ("faddp", "", VHSDF),
+        "addp" => ("addp", "", VDQ_I),
+        "sqdmulh" => ("sqdmulh", "", VSDQ_HSI),
+        "sqrdmulh" => ("sqrdmulh", "", VSDQ_HSI),
+        "sshl" => ("sshl", "", VSDQ_I_DI),
+        "ushl" => ("ushl", "_uus", VSDQ_I_DI),
+        "srshl" => ("srshl", "", VSDQ_I_DI),
+        "urshl" => ("urshl", "_uus", VSDQ_I_DI),
+        "sqshl" => ("sqshl", "", VSDQ_I),
+        "uqshl" => ("uqshl", "_uus", VSDQ_I),
+        "sqrshl" => ("sqrshl", "", VSDQ_I),
+        "uqrshl" => ("uqrshl", "_uus", VSDQ_I),
+        "fmax" => ("smax_nan", "", VHSDF),
+        "fmin" => ("smin_nan", "", VHSDF),
+        "fmaxnm" => ("fmax", "", VHSDF_DF),
+        "fminnm" => ("fmin", "", VHSDF_DF),
+        "fmaxp" => ("smax_nanp", "", VHSDF),
+        "fminp" => ("smin_nanp", "", VHSDF),
+        "fmaxnmp" => ("smaxp", "", VHSDF),
+        "fminnmp" => ("sminp", "", VHSDF),
+        "fmulx" => ("fmulx", "", VHSDF_HSDF),
+        "frecpe" => ("frecpe", "", VHSDF_HSDF),
+        "frecps" => ("frecps", "", VHSDF_HSDF),
+        "frsqrte" => ("rsqrte", "", VHSDF_HSDF),
+        "frsqrts" => ("rsqrts", "", VHSDF_HSDF),
+        "saddv" => ("reduc_plus_scal_", "", VDQ_I),
+        "faddv" => ("reduc_plus_scal_", "", VHSDF),
+        "uaddv" => ("reduc_plus_scal_", "_uu", VDQ_I),
+        "smaxv" => ("reduc_smax_scal_", "", VDQ_BHSI),
+        "umaxv" => ("reduc_umax_scal_", "_uu", VDQ_BHSI),
+        "sminv" => ("reduc_smin_scal_", "", VDQ_BHSI),
+        "uminv" => ("reduc_umin_scal_", "_uu", VDQ_BHSI),
+        "fmaxv" => ("reduc_smax_nan_scal_", "", VHSDF),
+        "fminv" => ("reduc_smin_nan_scal_", "", VHSDF),
+        "fmaxnmv" => ("reduc_smax_scal_", "", VHSDF),
+        "fminnmv" => ("reduc_smin_scal_", "", VHSDF),
+        _ => return None,
+    };
+    if !modes.contains(&mode) {
+        return None;
+    }
+    Some(format!("__builtin_aarch64_{}{}{}", builtin, mode, suffix))
 }
 
 #[cfg(feature = "master")]
-pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function<'gcc> {
+pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Option<Function<'gcc>> {
+    if let Some(gcc_name) = aarch64_neon_builtin(name) {
+        let func = cx.context.get_target_builtin_function(&gcc_name);
+        cx.functions.borrow_mut().insert(gcc_name, func);
+        return Some(func);
+    }
+
     let gcc_name = match name {
         "llvm.prefetch" => {
             let gcc_name = "__builtin_prefetch";
             let func = cx.context.get_builtin_function(gcc_name);
             cx.functions.borrow_mut().insert(gcc_name.to_string(), func);
-            return func;
+            return Some(func);
         }
 
         "llvm.aarch64.isb" => {
@@ -824,7 +1123,7 @@ pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function
             let gcc_name = "__atomic_thread_fence";
             let func = cx.context.get_builtin_function(gcc_name);
             cx.functions.borrow_mut().insert(gcc_name.to_string(), func);
-            return func;
+            return Some(func);
         }
 
         "llvm.x86.xgetbv" => "__builtin_ia32_xgetbv",
@@ -1326,9 +1625,11 @@ pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function
         "llvm.x86.avx512fp16.mask.vfcmadd.cph.128" => "__builtin_ia32_vfcmaddcph128_mask3",
         "llvm.x86.avx512fp16.mask.vfmadd.cph.128" => "__builtin_ia32_vfmaddcph128_mask3",
 
-        // TODO: support the tile builtins:
-        "llvm.x86.ldtilecfg" => "__builtin_trap",
-        "llvm.x86.sttilecfg" => "__builtin_trap",
+        "llvm.x86.ldtilecfg" => "__builtin_ia32_ldtilecfg",
+        "llvm.x86.sttilecfg" => "__builtin_ia32_sttilecfg",
+        // NOTE: GCC has no builtins for the other tile instructions since the tile registers are
+        // encoded in the instructions. The calls to these intrinsics are replaced by inline asm
+        // in amx_tile_intrinsic(), so this builtin is only used to declare them.
         "llvm.x86.tileloadd64" => "__builtin_trap",
         "llvm.x86.tilerelease" => "__builtin_trap",
         "llvm.x86.tilestored64" => "__builtin_trap",
@@ -1349,5 +1650,5 @@ pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function
 
     let func = cx.context.get_target_builtin_function(gcc_name);
     cx.functions.borrow_mut().insert(gcc_name.to_string(), func);
-    func
+    Some(func)
 }
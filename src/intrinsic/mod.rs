@@ -36,6 +36,7 @@ use crate::abi::GccType;
 use crate::builder::Builder;
 use crate::common::{SignType, TypeReflection};
 use crate::context::CodegenCx;
+use crate::errors::VaArgUnsupported;
 use crate::intrinsic::simd::generic_simd_intrinsic;
 use crate::type_of::LayoutGccExt;
 
@@ -158,10 +159,27 @@ impl<'a, 'gcc, 'tcx> IntrinsicCallMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
                 unimplemented!();
             }
             sym::va_copy => {
-                unimplemented!();
+                // NOTE: on every target supported by GCC, `va_copy` is a plain copy of the
+                // `va_list`.
+                let va_list_ty = arg_tys[1].builtin_deref(true).expect("va_list reference");
+                let va_list_layout = self.layout_of(va_list_ty);
+                let align = va_list_layout.align.abi;
+                let size = self.const_usize(va_list_layout.size.bytes());
+                self.memcpy(
+                    args[0].immediate(),
+                    align,
+                    args[1].immediate(),
+                    align,
+                    size,
+                    MemFlags::empty(),
+                );
+                return Ok(());
             }
             sym::va_arg => {
-                unimplemented!();
+                // TODO: use __builtin_va_arg when libgccjit supports it: it is not a builtin
+                // function in GCC, but a special expression of the C frontend.
+                tcx.dcx().emit_err(VaArgUnsupported { span });
+                return Ok(());
             }
 
             sym::volatile_load | sym::unaligned_volatile_load => {
@@ -433,11 +451,14 @@ impl<'a, 'gcc, 'tcx> IntrinsicCallMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
     }
 
     fn va_start(&mut self, _va_list: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+        // TODO: use __builtin_va_start when libgccjit supports the va_list type, which is needed to
+        // declare this builtin.
+        bug!("the C-variadic function definitions are rejected in predefine_fn()")
     }
 
-    fn va_end(&mut self, _va_list: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    fn va_end(&mut self, va_list: RValue<'gcc>) -> RValue<'gcc> {
+        // NOTE: `va_end` does nothing on every target supported by GCC.
+        va_list
     }
 }
 
@@ -1,30 +1,44 @@
 use std::iter::FromIterator;
 
-use gccjit::{BinaryOp, RValue, ToRValue, Type};
 #[cfg(feature = "master")]
-use gccjit::{ComparisonOp, UnaryOp};
+use gccjit::UnaryOp;
+use gccjit::{BinaryOp, ComparisonOp, RValue, ToRValue, Type};
 use rustc_codegen_ssa::base::compare_simd_types;
 use rustc_codegen_ssa::common::{IntPredicate, TypeKind};
-#[cfg(feature = "master")]
-use rustc_codegen_ssa::errors::ExpectedPointerMutability;
-use rustc_codegen_ssa::errors::InvalidMonomorphization;
+use rustc_codegen_ssa::errors::{ExpectedPointerMutability, InvalidMonomorphization};
 use rustc_codegen_ssa::mir::operand::OperandRef;
 use rustc_codegen_ssa::mir::place::PlaceRef;
 use rustc_codegen_ssa::traits::{BaseTypeMethods, BuilderMethods};
-#[cfg(feature = "master")]
 use rustc_hir as hir;
 use rustc_middle::mir::BinOp;
 use rustc_middle::span_bug;
 use rustc_middle::ty::layout::HasTyCtxt;
 use rustc_middle::ty::{self, Ty};
 use rustc_span::{sym, Span, Symbol};
-use rustc_target::abi::{Align, Size};
+use rustc_target::abi::Endian;
 
 use crate::builder::Builder;
 #[cfg(not(feature = "master"))]
 use crate::common::SignType;
-#[cfg(feature = "master")]
 use crate::context::CodegenCx;
+use crate::errors::UnsupportedSimdIntrinsic;
+
+/// Position, in the bitmask integer, of the bit of the given lane: the lanes are stored
+/// LSB-first on little endian targets and MSB-first on big endian targets.
+fn bitmask_bit_position(cx: &CodegenCx<'_, '_>, lane: u64, lane_count: u64) -> u64 {
+    match cx.sess().target.options.endian {
+        Endian::Little => lane,
+        Endian::Big => lane_count - 1 - lane,
+    }
+}
+
+/// Index of the byte holding the bit at `position` when the bitmask integer is stored in memory.
+fn bitmask_byte_index(cx: &CodegenCx<'_, '_>, position: u64, byte_count: u64) -> u64 {
+    match cx.sess().target.options.endian {
+        Endian::Little => position / 8,
+        Endian::Big => byte_count - 1 - position / 8,
+    }
+}
 
 pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
     bx: &mut Builder<'a, 'gcc, 'tcx>,
@@ -71,19 +85,15 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         let expected_bytes = len / 8 + ((len % 8 > 0) as u64);
 
         let mask_ty = arg_tys[0];
-        let mut mask = match *mask_ty.kind() {
-            ty::Int(i) if i.bit_width() == Some(expected_int_bits) => args[0].immediate(),
-            ty::Uint(i) if i.bit_width() == Some(expected_int_bits) => args[0].immediate(),
+        let mask_is_array = match *mask_ty.kind() {
+            ty::Int(i) if i.bit_width() == Some(expected_int_bits) => false,
+            ty::Uint(i) if i.bit_width() == Some(expected_int_bits) => false,
             ty::Array(elem, len)
                 if matches!(*elem.kind(), ty::Uint(ty::UintTy::U8))
                     && len.try_eval_target_usize(bx.tcx, ty::ParamEnv::reveal_all())
                         == Some(expected_bytes) =>
             {
-                let place = PlaceRef::alloca(bx, args[0].layout);
-                args[0].val.store(bx, place);
-                let int_ty = bx.type_ix(expected_bytes * 8);
-                let ptr = bx.pointercast(place.val.llval, bx.cx.type_ptr_to(int_ty));
-                bx.load(int_ty, ptr, Align::ONE)
+                true
             }
             _ => return_error!(InvalidMonomorphization::InvalidBitmask {
                 span,
@@ -106,11 +116,32 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
             bx.context.new_vector_type(mask_element_type, arg1_vector_type.get_num_units() as u64);
 
         let mut elements = vec![];
-        let one = bx.context.new_rvalue_one(mask.get_type());
-        for _ in 0..len {
-            let element = bx.context.new_cast(None, mask & one, mask_element_type);
-            elements.push(element);
-            mask = mask >> one;
+        if mask_is_array {
+            // NOTE: read the mask one byte at a time since it can be wider than the biggest
+            // integer type.
+            let place = PlaceRef::alloca(bx, args[0].layout);
+            args[0].val.store(bx, place);
+            let u8_type = bx.type_u8();
+            let bytes = bx.pointercast(place.val.llval, bx.cx.type_ptr_to(u8_type));
+            let one = bx.context.new_rvalue_one(u8_type);
+            for lane in 0..len {
+                let position = bitmask_bit_position(bx, lane, len);
+                let byte_index = bitmask_byte_index(bx, position, expected_bytes);
+                let byte_index = bx.context.new_rvalue_from_long(bx.usize_type, byte_index as i64);
+                let byte = bx.context.new_array_access(None, bytes, byte_index).to_rvalue();
+                let shift = bx.context.new_rvalue_from_int(u8_type, (position % 8) as i32);
+                let bit = (byte >> shift) & one;
+                elements.push(bx.context.new_cast(None, bit, mask_element_type));
+            }
+        } else {
+            let mask = args[0].immediate();
+            let one = bx.context.new_rvalue_one(mask.get_type());
+            for lane in 0..len {
+                let position = bitmask_bit_position(bx, lane, len);
+                let shift = bx.context.new_rvalue_from_int(mask.get_type(), position as i32);
+                let bit = (mask >> shift) & one;
+                elements.push(bx.context.new_cast(None, bit, mask_element_type));
+            }
         }
         let vector_mask = bx.context.new_rvalue_from_vector(None, vector_mask_type, &elements);
 
@@ -676,27 +707,28 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         let expected_int_bits = in_len.max(8);
         let expected_bytes = expected_int_bits / 8 + ((expected_int_bits % 8 > 0) as u64);
 
-        // FIXME(antoyo): that's not going to work for masks bigger than 128 bits.
-        let result_type = bx.type_ix(expected_int_bits);
-        let mut result = bx.context.new_rvalue_zero(result_type);
-
         let elem_size = elem_type.get_size() * 8;
         let sign_shift = bx.context.new_rvalue_from_int(elem_type, elem_size as i32 - 1);
         let one = bx.context.new_rvalue_one(elem_type);
-
-        for i in 0..in_len {
-            let elem =
-                bx.extract_element(vector, bx.context.new_rvalue_from_int(bx.int_type, i as i32));
-            let shifted = elem >> sign_shift;
-            let masked = shifted & one;
-            result = result
-                | (bx.context.new_cast(None, masked, result_type)
-                    << bx.context.new_rvalue_from_int(result_type, i as i32));
-        }
+        let most_significant_bits: Vec<_> = (0..in_len)
+            .map(|i| {
+                let elem = bx
+                    .extract_element(vector, bx.context.new_rvalue_from_int(bx.int_type, i as i32));
+                let shifted = elem >> sign_shift;
+                shifted & one
+            })
+            .collect();
 
         match *ret_ty.kind() {
             ty::Uint(i) if i.bit_width() == Some(expected_int_bits) => {
-                // Zero-extend iN to the bitmask type:
+                let result_type = bx.type_ix(expected_int_bits);
+                let mut result = bx.context.new_rvalue_zero(result_type);
+                for (lane, &bit) in most_significant_bits.iter().enumerate() {
+                    let position = bitmask_bit_position(bx, lane as u64, in_len);
+                    result = result
+                        | (bx.context.new_cast(None, bit, result_type)
+                            << bx.context.new_rvalue_from_int(result_type, position as i32));
+                }
                 return Ok(result);
             }
             ty::Array(elem, len)
@@ -704,15 +736,19 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
                     && len.try_eval_target_usize(bx.tcx, ty::ParamEnv::reveal_all())
                         == Some(expected_bytes) =>
             {
-                // Zero-extend iN to the array length:
-                let ze = bx.zext(result, bx.type_ix(expected_bytes * 8));
-
-                // Convert the integer to a byte array
-                let ptr = bx.alloca(Size::from_bytes(expected_bytes), Align::ONE);
-                bx.store(ze, ptr, Align::ONE);
-                let array_ty = bx.type_array(bx.type_i8(), expected_bytes);
-                let ptr = bx.pointercast(ptr, bx.cx.type_ptr_to(array_ty));
-                return Ok(bx.load(array_ty, ptr, Align::ONE));
+                // NOTE: build the array one byte at a time since it can be wider than the biggest
+                // integer type.
+                let u8_type = bx.type_u8();
+                let mut bytes = vec![bx.context.new_rvalue_zero(u8_type); expected_bytes as usize];
+                for (lane, &bit) in most_significant_bits.iter().enumerate() {
+                    let position = bitmask_bit_position(bx, lane as u64, in_len);
+                    let byte_index = bitmask_byte_index(bx, position, expected_bytes) as usize;
+                    let shift = bx.context.new_rvalue_from_int(u8_type, (position % 8) as i32);
+                    bytes[byte_index] =
+                        bytes[byte_index] | (bx.context.new_cast(None, bit, u8_type) << shift);
+                }
+                let array_ty = bx.type_array(u8_type, expected_bytes);
+                return Ok(bx.context.new_array_constructor(None, array_ty, &bytes));
             }
             _ => return_error!(InvalidMonomorphization::CannotReturn {
                 span,
@@ -760,9 +796,22 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
 
         let vec_ty = bx.cx.type_vector(elem_ty, in_len);
 
+        if cast_type.is_none()
+            && name == sym::simd_fabs
+            && let Some(result) = vector_fabs(bx, args[0].immediate(), elem_ty, vec_ty)
+        {
+            return Ok(result);
+        }
+        #[cfg(feature = "master")]
+        if cast_type.is_none()
+            && let Some(result) = target_vector_float_intrinsic(bx, name, args, elem_ty, in_len)
+        {
+            return Ok(bx.context.new_bitcast(None, result, vec_ty));
+        }
+
         let intr_name = match name {
             sym::simd_ceil => "ceil",
-            sym::simd_fabs => "fabs", // TODO(antoyo): pand with 170141183420855150465331762880109871103
+            sym::simd_fabs => "fabs",
             sym::simd_fcos => "cos",
             sym::simd_fexp2 => "exp2",
             sym::simd_fexp => "exp",
@@ -782,8 +831,10 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         let builtin_name = format!("{}{}", intr_name, elem_ty_str);
         let function = bx.context.get_builtin_function(builtin_name);
 
-        // TODO(antoyo): add platform-specific behavior here for architectures that have these
-        // intrinsics as instructions (for instance, gpus)
+        // NOTE: the operations that the target cannot do on whole vectors are done element by
+        // element with the libm functions.
+        // TODO(antoyo): add platform-specific behavior here for the other architectures that have
+        // these intrinsics as instructions (for instance, gpus)
         let mut vector_elements = vec![];
         for i in 0..in_len {
             let index = bx.context.new_rvalue_from_long(bx.ulong_type, i as i64);
@@ -815,6 +866,106 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         Ok(c)
     }
 
+    /// Clears the sign bit of every element instead of calling `fabs` for each of them.
+    /// Returns `None` for the float types that must be handled one element at a time.
+    fn vector_fabs<'gcc, 'tcx>(
+        bx: &mut Builder<'_, 'gcc, 'tcx>,
+        vector: RValue<'gcc>,
+        elem_ty: Type<'gcc>,
+        vec_ty: Type<'gcc>,
+    ) -> Option<RValue<'gcc>> {
+        let vector_type = vec_ty.dyncast_vector().expect("vector type");
+        let int_elem_ty = bx.type_ix(elem_ty.get_size() as u64 * 8);
+        let int_vec_ty =
+            bx.context.new_vector_type(int_elem_ty, vector_type.get_num_units() as u64);
+        let mask_value = match elem_ty.get_size() {
+            4 => bx.context.new_rvalue_from_int(int_elem_ty, i32::MAX),
+            8 => bx.context.new_rvalue_from_long(int_elem_ty, i64::MAX),
+            _ => return None,
+        };
+        let mask_elements = vec![mask_value; vector_type.get_num_units()];
+        let mask = bx.context.new_rvalue_from_vector(None, int_vec_ty, &mask_elements);
+        let vector = bx.context.new_bitcast(None, vector, int_vec_ty);
+        Some(bx.context.new_bitcast(None, vector & mask, vec_ty))
+    }
+
+    /// Lowers the float intrinsic to a builtin operating on the whole vector, if the target has
+    /// one.
+    #[cfg(feature = "master")]
+    fn target_vector_float_intrinsic<'gcc, 'tcx>(
+        bx: &mut Builder<'_, 'gcc, 'tcx>,
+        name: Symbol,
+        args: &[OperandRef<'tcx, RValue<'gcc>>],
+        elem_ty: Type<'gcc>,
+        in_len: u64,
+    ) -> Option<RValue<'gcc>> {
+        let sess = bx.sess();
+        let has_feature = |feature: &str| sess.target_features.contains(&Symbol::intern(feature));
+        let elem_size = elem_ty.get_size();
+        let (builtin_name, rounding_mode) = match sess.target.arch.as_ref() {
+            "x86" | "x86_64" => {
+                let (suffix, base_feature) = match (elem_size, in_len) {
+                    (4, 4) => ("ps", "sse"),
+                    (8, 2) => ("pd", "sse2"),
+                    (4, 8) => ("ps256", "avx"),
+                    (8, 4) => ("pd256", "avx"),
+                    _ => return None,
+                };
+                let round_feature = if suffix.ends_with("256") { "avx" } else { "sse4.1" };
+                // NOTE: the rounding mode immediates of the round builtins, without the
+                // precision exception.
+                let (operation, feature, rounding_mode) = match name {
+                    sym::simd_fsqrt => ("sqrt", base_feature, None),
+                    sym::simd_floor => ("round", round_feature, Some(0x9)),
+                    sym::simd_ceil => ("round", round_feature, Some(0xA)),
+                    sym::simd_trunc => ("round", round_feature, Some(0xB)),
+                    sym::simd_fma => ("vfmadd", "fma", None),
+                    _ => return None,
+                };
+                if !has_feature(feature) {
+                    return None;
+                }
+                (format!("__builtin_ia32_{}{}", operation, suffix), rounding_mode)
+            }
+            "aarch64" => {
+                let suffix = match (elem_size, in_len) {
+                    (4, 2) => "v2sf",
+                    (4, 4) => "v4sf",
+                    (8, 2) => "v2df",
+                    _ => return None,
+                };
+                let operation = match name {
+                    sym::simd_fsqrt => "sqrt",
+                    sym::simd_floor => "floor",
+                    sym::simd_ceil => "ceil",
+                    sym::simd_trunc => "btrunc",
+                    sym::simd_round => "round",
+                    sym::simd_fma => "fma",
+                    _ => return None,
+                };
+                if !has_feature("neon") {
+                    return None;
+                }
+                (format!("__builtin_aarch64_{}{}", operation, suffix), None)
+            }
+            _ => return None,
+        };
+
+        let func = bx.context.get_target_builtin_function(builtin_name);
+        let mut arguments: Vec<_> = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                let param_type = func.get_param(index as i32).to_rvalue().get_type();
+                bx.context.new_bitcast(None, arg.immediate(), param_type)
+            })
+            .collect();
+        if let Some(rounding_mode) = rounding_mode {
+            arguments.push(bx.context.new_rvalue_from_int(bx.int_type, rounding_mode));
+        }
+        Some(bx.context.new_call(None, func, &arguments))
+    }
+
     if std::matches!(
         name,
         sym::simd_ceil
@@ -884,7 +1035,7 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         for i in 0..in_len {
             let index = bx.context.new_rvalue_from_long(bx.i32_type, i as i64);
             mask_types.push(bx.context.new_field(None, bx.i32_type, "m"));
-            let mask_value = bx.context.new_vector_access(None, mask, index).to_rvalue();
+            let mask_value = bx.extract_element(mask, index);
             let mask_value_cast = bx.context.new_cast(None, mask_value, bx.i32_type);
             let masked =
                 bx.context.new_rvalue_from_int(bx.i32_type, in_len as i32) & mask_value_cast;
@@ -1155,6 +1306,253 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         return Ok(bx.context.new_rvalue_zero(bx.i32_type));
     }
 
+    /// Returns the name of the x86 masked move builtin for vectors of this type, if the target
+    /// has one.
+    #[cfg(feature = "master")]
+    fn masked_move_builtin<'a, 'gcc, 'tcx>(
+        bx: &Builder<'a, 'gcc, 'tcx>,
+        vector_type: Type<'gcc>,
+        mask_type: Type<'gcc>,
+        load: bool,
+    ) -> Option<String> {
+        if !matches!(bx.sess().target.arch.as_ref(), "x86" | "x86_64") {
+            return None;
+        }
+        let vector_type = vector_type.unqualified().dyncast_vector().expect("vector type");
+        let mask_type = mask_type.unqualified().dyncast_vector().expect("vector type");
+        let elem_type = vector_type.get_element_type();
+        let elem_size = elem_type.get_size();
+        // NOTE: the builtins only look at the most significant bit of each mask element, so the
+        // mask elements must be as wide as the value elements.
+        if mask_type.get_element_type().get_size() != elem_size {
+            return None;
+        }
+        let (feature, suffix) =
+            match (bx.cx.is_float_type(elem_type), elem_size, vector_type.get_num_units()) {
+                (true, 4, 4) => ("avx", "ps"),
+                (true, 4, 8) => ("avx", "ps256"),
+                (true, 8, 2) => ("avx", "pd"),
+                (true, 8, 4) => ("avx", "pd256"),
+                (false, 4, 4) => ("avx2", "d"),
+                (false, 4, 8) => ("avx2", "d256"),
+                (false, 8, 2) => ("avx2", "q"),
+                (false, 8, 4) => ("avx2", "q256"),
+                _ => return None,
+            };
+        if !bx.sess().target_features.contains(&Symbol::intern(feature)) {
+            return None;
+        }
+        let operation = if load { "load" } else { "store" };
+        Some(format!("__builtin_ia32_mask{}{}", operation, suffix))
+    }
+
+    /// Emits a block that is only executed for the lanes whose mask element is not zero.
+    fn for_each_enabled_lane<'a, 'gcc, 'tcx>(
+        bx: &mut Builder<'a, 'gcc, 'tcx>,
+        mask: RValue<'gcc>,
+        in_len: u64,
+        mut lane: impl FnMut(&mut Builder<'a, 'gcc, 'tcx>, RValue<'gcc>),
+    ) {
+        for i in 0..in_len {
+            let index = bx.context.new_rvalue_from_int(bx.int_type, i as i32);
+            let mask_value = bx.extract_element(mask, index);
+            let zero = bx.context.new_rvalue_zero(mask_value.get_type());
+            let enabled =
+                bx.context.new_comparison(None, ComparisonOp::NotEquals, mask_value, zero);
+            let lane_block = bx.append_sibling_block("masked_lane");
+            let next_block = bx.append_sibling_block("masked_lane_next");
+            bx.cond_br(enabled, lane_block, next_block);
+            bx.switch_to_block(lane_block);
+            lane(bx, index);
+            bx.br(next_block);
+            bx.switch_to_block(next_block);
+        }
+    }
+
+    if name == sym::simd_masked_load {
+        // simd_masked_load(mask: <N x i{M}>, pointer: *_ T, values: <N x T>) -> <N x T>
+        // * N: number of elements in the input vectors
+        // * T: type of the element to load
+        // * M: any integer width is supported, will be truncated to i1
+        // Loads contiguous elements from memory behind `pointer`, but only for
+        // those lanes whose `mask` bit is enabled.
+        // The memory addresses corresponding to the “off” lanes are not accessed.
+
+        let mask_ty = in_ty;
+        let mask_len = in_len;
+        let pointer_ty = arg_tys[1];
+        let values_ty = arg_tys[2];
+        require_simd!(values_ty, InvalidMonomorphization::SimdThird { span, name, ty: values_ty });
+        require_simd!(ret_ty, InvalidMonomorphization::SimdReturn { span, name, ty: ret_ty });
+        let (values_len, values_elem) = values_ty.simd_size_and_type(bx.tcx());
+
+        // Of the same length:
+        require!(
+            values_len == mask_len,
+            InvalidMonomorphization::ThirdArgumentLength {
+                span,
+                name,
+                in_len: mask_len,
+                in_ty: mask_ty,
+                arg_ty: values_ty,
+                out_len: values_len
+            }
+        );
+
+        // The return type must match the last argument type
+        require!(
+            ret_ty == values_ty,
+            InvalidMonomorphization::ExpectedReturnType { span, name, in_ty: values_ty, ret_ty }
+        );
+
+        require!(
+            matches!(*pointer_ty.kind(), ty::RawPtr(p_ty, _) if p_ty == values_elem),
+            InvalidMonomorphization::ExpectedElementType {
+                span,
+                name,
+                expected_element: values_elem,
+                second_arg: pointer_ty,
+                in_elem: values_elem,
+                in_ty: values_ty,
+                mutability: ExpectedPointerMutability::Not,
+            }
+        );
+
+        // The element type of the mask must be a signed integer type of any width:
+        require!(
+            matches!(*in_elem.kind(), ty::Int(_)),
+            InvalidMonomorphization::ThirdArgElementType {
+                span,
+                name,
+                expected_element: values_elem,
+                third_arg: mask_ty
+            }
+        );
+
+        let mask = args[0].immediate();
+        let pointer = args[1].immediate();
+        let values = args[2].immediate();
+        let vector_type = values.get_type();
+
+        #[cfg(feature = "master")]
+        if let Some(builtin_name) =
+            masked_move_builtin(bx, vector_type, mask.get_type(), /* load */ true)
+        {
+            let func = bx.context.get_target_builtin_function(builtin_name);
+            let pointer_type = func.get_param(0).to_rvalue().get_type();
+            let builtin_mask_type = func.get_param(1).to_rvalue().get_type();
+            let pointer = bx.context.new_cast(None, pointer, pointer_type);
+            let builtin_mask = bx.context.new_bitcast(None, mask, builtin_mask_type);
+            let loaded = bx.context.new_call(None, func, &[pointer, builtin_mask]);
+            let loaded = bx.context.new_bitcast(None, loaded, vector_type);
+            // NOTE: the builtin sets the disabled lanes to zero.
+            return Ok(bx.vector_select(mask, loaded, values));
+        }
+
+        let elem_type =
+            vector_type.unqualified().dyncast_vector().expect("vector type").get_element_type();
+        let pointer = bx.context.new_cast(None, pointer, elem_type.make_pointer());
+        let result = bx.current_func().new_local(None, vector_type, "masked_load_result");
+        bx.llbb().add_assignment(None, result, values);
+        let result_elements =
+            bx.context.new_cast(None, result.get_address(None), elem_type.make_pointer());
+        for_each_enabled_lane(bx, mask, in_len, |bx, index| {
+            let value = bx.context.new_array_access(None, pointer, index).to_rvalue();
+            let lane = bx.context.new_array_access(None, result_elements, index);
+            bx.llbb().add_assignment(None, lane, value);
+        });
+        return Ok(result.to_rvalue());
+    }
+
+    if name == sym::simd_masked_store {
+        // simd_masked_store(mask: <N x i{M}>, pointer: *mut T, values: <N x T>) -> ()
+        // * N: number of elements in the input vectors
+        // * T: type of the element to load
+        // * M: any integer width is supported, will be truncated to i1
+        // Stores contiguous elements to memory behind `pointer`, but only for
+        // those lanes whose `mask` bit is enabled.
+        // The memory addresses corresponding to the “off” lanes are not accessed.
+
+        let mask_ty = in_ty;
+        let mask_len = in_len;
+        let pointer_ty = arg_tys[1];
+        let values_ty = arg_tys[2];
+        require_simd!(values_ty, InvalidMonomorphization::SimdThird { span, name, ty: values_ty });
+        let (values_len, values_elem) = values_ty.simd_size_and_type(bx.tcx());
+
+        // Of the same length:
+        require!(
+            values_len == mask_len,
+            InvalidMonomorphization::ThirdArgumentLength {
+                span,
+                name,
+                in_len: mask_len,
+                in_ty: mask_ty,
+                arg_ty: values_ty,
+                out_len: values_len
+            }
+        );
+
+        // The second argument must be a mutable pointer type matching the element type
+        require!(
+            matches!(
+                *pointer_ty.kind(),
+                ty::RawPtr(p_ty, hir::Mutability::Mut) if p_ty == values_elem
+            ),
+            InvalidMonomorphization::ExpectedElementType {
+                span,
+                name,
+                expected_element: values_elem,
+                second_arg: pointer_ty,
+                in_elem: values_elem,
+                in_ty: values_ty,
+                mutability: ExpectedPointerMutability::Mut,
+            }
+        );
+
+        // The element type of the mask must be a signed integer type of any width:
+        require!(
+            matches!(*in_elem.kind(), ty::Int(_)),
+            InvalidMonomorphization::ThirdArgElementType {
+                span,
+                name,
+                expected_element: values_elem,
+                third_arg: mask_ty
+            }
+        );
+
+        let mask = args[0].immediate();
+        let pointer = args[1].immediate();
+        let values = args[2].immediate();
+        let vector_type = values.get_type();
+
+        #[cfg(feature = "master")]
+        if let Some(builtin_name) =
+            masked_move_builtin(bx, vector_type, mask.get_type(), /* load */ false)
+        {
+            let func = bx.context.get_target_builtin_function(builtin_name);
+            let pointer_type = func.get_param(0).to_rvalue().get_type();
+            let builtin_mask_type = func.get_param(1).to_rvalue().get_type();
+            let builtin_values_type = func.get_param(2).to_rvalue().get_type();
+            let pointer = bx.context.new_cast(None, pointer, pointer_type);
+            let mask = bx.context.new_bitcast(None, mask, builtin_mask_type);
+            let values = bx.context.new_bitcast(None, values, builtin_values_type);
+            let call = bx.context.new_call(None, func, &[pointer, mask, values]);
+            bx.llbb().add_eval(None, call);
+            return Ok(bx.context.new_rvalue_zero(bx.i32_type));
+        }
+
+        let elem_type =
+            vector_type.unqualified().dyncast_vector().expect("vector type").get_element_type();
+        let pointer = bx.context.new_cast(None, pointer, elem_type.make_pointer());
+        for_each_enabled_lane(bx, mask, in_len, |bx, index| {
+            let value = bx.extract_element(values, index);
+            let lane = bx.context.new_array_access(None, pointer, index);
+            bx.llbb().add_assignment(None, lane, value);
+        });
+        return Ok(bx.context.new_rvalue_zero(bx.i32_type));
+    }
+
     arith_binary! {
         simd_add: Uint, Int => add, Float => fadd;
         simd_sub: Uint, Int => sub, Float => fsub;
@@ -1309,13 +1707,20 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
                         }
                     }
                     ty::Float(_) => {
-                        if $ordered {
+                        let acc = if $ordered {
                             // ordered arithmetic reductions take an accumulator
-                            let acc = args[1].immediate();
-                            Ok(bx.$float_reduce(acc, args[0].immediate()))
+                            args[1].immediate()
                         } else {
-                            Ok(bx.vector_reduce_op(args[0].immediate(), $vec_op))
-                        }
+                            let elem_type = args[0]
+                                .immediate()
+                                .get_type()
+                                .unqualified()
+                                .dyncast_vector()
+                                .expect("vector type")
+                                .get_element_type();
+                            bx.context.new_rvalue_from_double(elem_type, $identity)
+                        };
+                        Ok(bx.$float_reduce(acc, args[0].immediate()))
                     }
                     _ => return_error!(InvalidMonomorphization::UnsupportedSymbol {
                         span,
@@ -1335,7 +1740,7 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         vector_reduce_fadd_reassoc,
         false,
         add,
-        0.0 // TODO: Use this argument.
+        -0.0
     );
     arith_red!(
         simd_reduce_mul_unordered: BinaryOp::Mult,
@@ -1349,7 +1754,7 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
         vector_reduce_fadd,
         true,
         add,
-        0.0
+        -0.0
     );
     arith_red!(
         simd_reduce_mul_ordered: BinaryOp::Mult,
@@ -1441,5 +1846,5 @@ pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(
     bitwise_red!(simd_reduce_all: BinaryOp::BitwiseAnd, true);
     bitwise_red!(simd_reduce_any: BinaryOp::BitwiseOr, true);
 
-    unimplemented!("simd {}", name);
+    return_error!(UnsupportedSimdIntrinsic { span, name, arch: &bx.sess().target.arch });
 }
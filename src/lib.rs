@@ -79,10 +79,10 @@ use std::ops::Deref;
 use std::sync::atomic::AtomicBool;
 #[cfg(not(feature = "master"))]
 use std::sync::atomic::Ordering;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use back::lto::{ThinBuffer, ThinData};
-use errors::LTONotSupported;
+use errors::{LTONotSupported, UnsupportedSanitizer};
 use gccjit::{CType, Context, OptimizationLevel};
 #[cfg(feature = "master")]
 use gccjit::{TargetInfo, Version};
@@ -101,10 +101,14 @@ use rustc_metadata::EncodedMetadata;
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::ty::TyCtxt;
 use rustc_middle::util::Providers;
-use rustc_session::config::{Lto, OptLevel, OutputFilenames};
+use rustc_session::config::{
+    BranchProtection, CFProtection, FunctionReturn, Lto, OptLevel, OutputFilenames, PAuthKey,
+    PacRet,
+};
 use rustc_session::Session;
 use rustc_span::fatal_error::FatalError;
 use rustc_span::Symbol;
+use rustc_target::spec::{FramePointer, RelocModel, SanitizerSet, StackProbeType, StackProtector};
 use tempfile::TempDir;
 
 use crate::back::lto::ModuleBuffer;
@@ -150,6 +154,7 @@ impl TargetInfo {
 #[derive(Clone)]
 pub struct LockedTargetInfo {
     info: Arc<Mutex<IntoDynSyncSend<TargetInfo>>>,
+    supports_retain: Arc<OnceLock<bool>>,
 }
 
 impl Debug for LockedTargetInfo {
@@ -166,6 +171,28 @@ impl LockedTargetInfo {
     fn supports_target_dependent_type(&self, typ: CType) -> bool {
         self.info.lock().expect("lock").supports_target_dependent_type(typ)
     }
+
+    /// Whether the assembler supports the SHF_GNU_RETAIN section flag, which requires binutils
+    /// 2.36. This is checked by assembling a retained section with libgccjit, so that the same
+    /// assembler is used.
+    /// NOTE: the check is done lazily, so only the sessions that retain a global pay for it, and
+    /// at most once per session since the result is shared by all the codegen units.
+    pub fn supports_retain(&self) -> bool {
+        *self.supports_retain.get_or_init(|| {
+            let Ok(temp_dir) = tempfile::tempdir() else {
+                return false;
+            };
+            let context = Context::default();
+            context.set_print_errors_to_stderr(false);
+            context.add_top_level_asm(None, &consts::retain_asm("rustc_retain_check"));
+            let path = temp_dir.path().join("retain.o");
+            context.compile_to_file(
+                gccjit::OutputKind::ObjectFile,
+                path.to_str().expect("path to str"),
+            );
+            matches!(context.get_first_error(), Ok(None))
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -199,6 +226,14 @@ impl CodegenBackend for GccCodegenBackend {
             sess.dcx().emit_warn(LTONotSupported {});
         }
 
+        for sanitizer in sess.opts.unstable_opts.sanitizer {
+            if gcc_util::sanitizer_to_gcc(sanitizer).is_none() {
+                sess.dcx().emit_err(UnsupportedSanitizer {
+                    sanitizer: sanitizer.as_str().unwrap_or("unknown"),
+                });
+            }
+        }
+
         #[cfg(not(feature = "master"))]
         {
             let temp_dir = TempDir::new().expect("cannot create temporary directory");
@@ -286,11 +321,196 @@ fn new_context<'gcc, 'tcx>(tcx: TyCtxt<'tcx>) -> Context<'gcc> {
             version,
         ));
     }
-    // TODO(antoyo): check if this should only be added when using -Cforce-unwind-tables=n.
-    context.add_command_line_option("-fno-asynchronous-unwind-tables");
+    // NOTE: the unwind tables are always explicitly enabled or disabled, because the default
+    // depends on how GCC was configured.
+    if tcx.sess.must_emit_unwind_tables() {
+        context.add_command_line_option("-funwind-tables");
+        if tcx.sess.opts.unstable_opts.use_sync_unwind.unwrap_or(false) {
+            context.add_command_line_option("-fno-asynchronous-unwind-tables");
+        } else {
+            context.add_command_line_option("-fasynchronous-unwind-tables");
+        }
+    } else {
+        context.add_command_line_option("-fno-asynchronous-unwind-tables");
+        context.add_command_line_option("-fno-unwind-tables");
+    }
+
+    // NOTE: the stack protector and the stack probes are always explicitly enabled or disabled,
+    // because some distributions configure GCC to enable them by default.
+    context.add_command_line_option(match tcx.sess.stack_protector() {
+        StackProtector::None => "-fno-stack-protector",
+        StackProtector::Basic => "-fstack-protector",
+        StackProtector::Strong => "-fstack-protector-strong",
+        StackProtector::All => "-fstack-protector-all",
+    });
+    if needs_stack_probes(tcx.sess) {
+        context.add_command_line_option("-fstack-clash-protection");
+    } else {
+        context.add_command_line_option("-fno-stack-clash-protection");
+    }
+
+    add_relocation_model_options(&context, tcx.sess);
+    add_control_flow_protection_options(&context, tcx);
+
+    // NOTE: libgccjit cannot set the frame pointer policy and the red zone per function, but
+    // rustc uses the same policy for every function, so the command-line options are enough.
+    let arch = tcx.sess.target.arch.as_ref();
+    let has_leaf_frame_pointer_option = matches!(arch, "x86" | "x86_64" | "aarch64");
+    match frame_pointer_type(tcx.sess) {
+        FramePointer::Always => {
+            context.add_command_line_option("-fno-omit-frame-pointer");
+            if has_leaf_frame_pointer_option {
+                context.add_command_line_option("-mno-omit-leaf-frame-pointer");
+            }
+        }
+        FramePointer::NonLeaf => {
+            context.add_command_line_option("-fno-omit-frame-pointer");
+            if has_leaf_frame_pointer_option {
+                context.add_command_line_option("-momit-leaf-frame-pointer");
+            }
+        }
+        FramePointer::MayOmit => (),
+    }
+    if arch == "x86_64" && tcx.sess.opts.cg.no_redzone.unwrap_or(tcx.sess.target.disable_redzone) {
+        context.add_command_line_option("-mno-red-zone");
+    }
+
+    if gcc_util::has_soft_float(tcx.sess) {
+        // NOTE: the floating-point operations are lowered to library calls (e.g. `__addsf3`),
+        // which are provided by compiler-builtins.
+        match tcx.sess.target.arch.as_ref() {
+            "x86" | "x86_64" => context.add_command_line_option("-msoft-float"),
+            "aarch64" => context.add_command_line_option("-mgeneral-regs-only"),
+            "arm" => context.add_command_line_option("-mfloat-abi=soft"),
+            _ => (),
+        }
+    }
+
     context
 }
 
+/// The relocation model is always explicitly set, because the default of GCC depends on how it was
+/// configured (e.g. `--enable-default-pie`).
+fn add_relocation_model_options(context: &Context<'_>, sess: &Session) {
+    let arch = sess.target.arch.as_ref();
+    match sess.relocation_model() {
+        RelocModel::Static => {
+            context.add_command_line_option("-fno-pic");
+            context.add_command_line_option("-fno-pie");
+        }
+        RelocModel::Pic => context.add_command_line_option("-fPIC"),
+        RelocModel::Pie => context.add_command_line_option("-fPIE"),
+        RelocModel::DynamicNoPic => {
+            if sess.target.is_like_osx {
+                context.add_command_line_option("-mdynamic-no-pic");
+            } else {
+                context.add_command_line_option("-fno-pic");
+            }
+        }
+        // NOTE: GCC has no equivalent of ROPI: the closest is to use position-independent code.
+        RelocModel::Ropi => context.add_command_line_option("-fPIC"),
+        RelocModel::Rwpi | RelocModel::RopiRwpi => {
+            context.add_command_line_option("-fPIC");
+            if arch == "arm" {
+                // NOTE: the data is accessed relative to the static base register (r9).
+                context.add_command_line_option("-mno-pic-data-is-text-relative");
+                context.add_command_line_option("-msingle-pic-base");
+                context.add_command_line_option("-mpic-register=r9");
+            }
+        }
+    }
+
+    if !sess.needs_plt() {
+        context.add_command_line_option("-fno-plt");
+    }
+
+    if matches!(arch, "x86" | "x86_64" | "loongarch64") {
+        match sess.direct_access_external_data() {
+            Some(true) => context.add_command_line_option("-mdirect-extern-access"),
+            Some(false) => context.add_command_line_option("-mno-direct-extern-access"),
+            None => (),
+        }
+    }
+}
+
+fn add_control_flow_protection_options(context: &Context<'_>, tcx: TyCtxt<'_>) {
+    let sess = tcx.sess;
+    let is_x86 = sess.target.arch == "x86" || sess.target.arch == "x86_64";
+
+    if is_x86 {
+        context.add_command_line_option(match sess.opts.unstable_opts.cf_protection {
+            CFProtection::None => "-fcf-protection=none",
+            CFProtection::Branch => "-fcf-protection=branch",
+            CFProtection::Return => "-fcf-protection=return",
+            CFProtection::Full => "-fcf-protection=full",
+        });
+
+        context.add_command_line_option(match sess.opts.unstable_opts.function_return {
+            FunctionReturn::Keep => "-mfunction-return=keep",
+            FunctionReturn::ThunkExtern => "-mfunction-return=thunk-extern",
+        });
+
+        // NOTE: the retpoline features are LLVM features that are passed through
+        // -Ctarget-feature, so they are not part of the features sent to the target attribute.
+        let features = tcx.global_backend_features(());
+        if gcc_util::is_feature_enabled(features, "retpoline-external-thunk") {
+            context.add_command_line_option("-mindirect-branch=thunk-extern");
+            context.add_command_line_option("-mindirect-branch-register");
+        } else if gcc_util::is_feature_enabled(features, "retpoline-indirect-branches")
+            || gcc_util::is_feature_enabled(features, "retpoline-indirect-calls")
+        {
+            context.add_command_line_option("-mindirect-branch=thunk");
+        }
+    }
+
+    if sess.target.arch == "aarch64" {
+        if let Some(BranchProtection { bti, pac_ret }) = sess.opts.unstable_opts.branch_protection {
+            let mut options = vec![];
+            if bti {
+                options.push("bti");
+            }
+            if let Some(PacRet { leaf, key }) = pac_ret {
+                options.push("pac-ret");
+                if leaf {
+                    options.push("leaf");
+                }
+                if key == PAuthKey::B {
+                    options.push("b-key");
+                }
+            }
+            let options = if options.is_empty() { "none".to_string() } else { options.join("+") };
+            context.add_command_line_option(format!("-mbranch-protection={}", options));
+        }
+    }
+}
+
+fn frame_pointer_type(sess: &Session) -> FramePointer {
+    let mut frame_pointer = sess.target.frame_pointer;
+    // "mcount" function relies on stack pointer.
+    // See <https://sourceware.org/binutils/docs/gprof/Implementation.html>.
+    if sess.opts.unstable_opts.instrument_mcount {
+        frame_pointer.ratchet(FramePointer::Always);
+    }
+    frame_pointer.ratchet(sess.opts.cg.force_frame_pointers);
+    frame_pointer
+}
+
+fn needs_stack_probes(sess: &Session) -> bool {
+    // Currently stack probes seem somewhat incompatible with the address
+    // sanitizer and thread sanitizer. With asan we're already protected from
+    // stack overflow anyway so we don't really need stack probes regardless.
+    if sess.opts.unstable_opts.sanitizer.intersects(SanitizerSet::ADDRESS | SanitizerSet::THREAD) {
+        return false;
+    }
+
+    match sess.target.stack_probes {
+        StackProbeType::None => false,
+        StackProbeType::Inline | StackProbeType::InlineOrCall { .. } => true,
+        // NOTE: GCC cannot call `__rust_probestack`: those targets don't get stack probes.
+        StackProbeType::Call => false,
+    }
+}
+
 impl ExtraBackendMethods for GccCodegenBackend {
     fn codegen_allocator(
         &self,
@@ -302,6 +522,7 @@ impl ExtraBackendMethods for GccCodegenBackend {
         let mut mods = GccContext {
             context: Arc::new(SyncContext::new(new_context(tcx))),
             should_combine_object_files: false,
+            function_link_sections: vec![],
             temp_dir: None,
         };
 
@@ -333,6 +554,9 @@ impl ExtraBackendMethods for GccCodegenBackend {
 pub struct GccContext {
     context: Arc<SyncContext>,
     should_combine_object_files: bool,
+    /// The functions with a `#[link_section]` (symbol name, section name), whose sections are set
+    /// after the object file is compiled.
+    function_link_sections: Vec<(String, String)>,
     // Temporary directory used by LTO. We keep it here so that it's not removed before linking.
     temp_dir: Option<TempDir>,
 }
@@ -461,7 +685,9 @@ pub fn __rustc_codegen_backend() -> Box<dyn CodegenBackend> {
         supports_128bit_integers: AtomicBool::new(false),
     })));
 
-    Box::new(GccCodegenBackend { target_info: LockedTargetInfo { info } })
+    Box::new(GccCodegenBackend {
+        target_info: LockedTargetInfo { info, supports_retain: Default::default() },
+    })
 }
 
 fn to_gcc_opt_level(optlevel: Option<OptLevel>) -> OptimizationLevel {
@@ -494,7 +720,8 @@ pub fn target_features(
             }
         })
         .filter(|feature| {
-            // TODO: we disable Neon for now since we don't support the LLVM intrinsics for it.
+            // TODO: we disable Neon for now since the loads, stores, lane and table lookup LLVM
+            // intrinsics used by core::arch::aarch64 are not mapped to the GCC builtins yet.
             if *feature == "neon" {
                 return false;
             }
@@ -1,3 +1,4 @@
+use gccjit::FunctionType;
 #[cfg(feature = "master")]
 use gccjit::{FnAttribute, VarAttribute};
 use rustc_codegen_ssa::traits::PreDefineMethods;
@@ -10,6 +11,7 @@ use rustc_middle::ty::layout::{FnAbiOf, LayoutOf};
 use rustc_middle::ty::{self, Instance, TypeVisitableExt};
 
 use crate::context::CodegenCx;
+use crate::errors::{CVariadicDefinitionUnsupported, UnsupportedLinkage};
 use crate::type_of::LayoutGccExt;
 use crate::{attributes, base};
 
@@ -18,7 +20,7 @@ impl<'gcc, 'tcx> PreDefineMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
     fn predefine_static(
         &self,
         def_id: DefId,
-        _linkage: Linkage,
+        linkage: Linkage,
         visibility: Visibility,
         symbol_name: &str,
     ) {
@@ -34,12 +36,26 @@ impl<'gcc, 'tcx> PreDefineMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
         };
         let gcc_type = self.layout_of(ty).gcc_type(self);
 
+        if base::global_linkage_to_gcc(linkage).is_none() {
+            self.tcx.dcx().emit_err(UnsupportedLinkage {
+                span: self.tcx.def_span(def_id),
+                linkage: format!("{:?}", linkage),
+            });
+        }
+
         let is_tls = attrs.flags.contains(CodegenFnAttrFlags::THREAD_LOCAL);
-        let global = self.define_global(symbol_name, gcc_type, is_tls, attrs.link_section);
+        let link_section =
+            attrs.link_section.filter(|&section| self.check_link_section(def_id, section));
+        let global = self.define_global(symbol_name, gcc_type, is_tls, link_section);
         #[cfg(feature = "master")]
         global.add_attribute(VarAttribute::Visibility(base::visibility_to_gcc(visibility)));
 
-        // TODO(antoyo): set linkage.
+        if base::linkage_is_weak(linkage) {
+            #[cfg(feature = "master")]
+            global.add_attribute(VarAttribute::Weak);
+        }
+
+        // TODO(antoyo): set the other linkages.
         self.instances.borrow_mut().insert(instance, global);
     }
 
@@ -54,12 +70,40 @@ impl<'gcc, 'tcx> PreDefineMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
         assert!(!instance.args.has_infer());
 
         let fn_abi = self.fn_abi_of_instance(instance, ty::List::empty());
-        self.linkage.set(base::linkage_to_gcc(linkage));
+        let attrs = self.tcx.codegen_fn_attrs(instance.def_id());
+        if attrs.flags.contains(CodegenFnAttrFlags::NAKED) {
+            // NOTE: naked functions are defined in global asm by codegen_naked_asm(), so only
+            // declare them here.
+            self.linkage.set(FunctionType::Extern);
+            let decl = self.declare_fn(symbol_name, fn_abi);
+            self.functions.borrow_mut().insert(symbol_name.to_string(), decl);
+            self.function_instances.borrow_mut().insert(instance, decl);
+            return;
+        }
+        if fn_abi.c_variadic {
+            // NOTE: libgccjit cannot express `va_start`, so the C-variadic functions are rejected
+            // here, before their codegen calls va_start().
+            let span = self.tcx.def_span(instance.def_id());
+            self.tcx.dcx().emit_fatal(CVariadicDefinitionUnsupported { span });
+        }
+        self.check_calling_convention(fn_abi, instance.def_id());
+        let function_type = base::linkage_to_gcc(linkage).unwrap_or_else(|| {
+            self.tcx.dcx().emit_err(UnsupportedLinkage {
+                span: self.tcx.def_span(instance.def_id()),
+                linkage: format!("{:?}", linkage),
+            });
+            FunctionType::Exported
+        });
+        self.linkage.set(function_type);
         let decl = self.declare_fn(symbol_name, fn_abi);
-        //let attrs = self.tcx.codegen_fn_attrs(instance.def_id());
 
         attributes::from_fn_attrs(self, decl, instance);
 
+        if base::linkage_is_weak(linkage) {
+            #[cfg(feature = "master")]
+            decl.add_attribute(FnAttribute::Weak);
+        }
+
         // If we're compiling the compiler-builtins crate, e.g., the equivalent of
         // compiler-rt, then we want to implicitly compile everything with hidden
         // visibility as we're going to link this object all over the place but
@@ -75,7 +119,13 @@ impl<'gcc, 'tcx> PreDefineMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
             decl.add_attribute(FnAttribute::Visibility(base::visibility_to_gcc(visibility)));
         }
 
-        // TODO(antoyo): call set_link_section() to allow initializing argc/argv.
+        let def_id = instance.def_id();
+        if let Some(section) = attrs.link_section {
+            if self.check_link_section(def_id, section) {
+                self.set_function_link_section(def_id, symbol_name, section);
+            }
+        }
+
         // TODO(antoyo): set unique comdat.
         // TODO(antoyo): use inline attribute from there in linkage.set() above.
 
@@ -39,6 +39,10 @@ pub fn main_inner(profile: Profile) {
     env::set_var("LD_LIBRARY_PATH", gcc_path);
 
     fn rust_filter(path: &Path) -> bool {
+        // NOTE: this test checks the emitted x86-64 asm.
+        if std::env::var("CG_GCC_TEST_TARGET").is_ok() && path.ends_with("amx_tile.rs") {
+            return false;
+        }
         path.is_file() && path.extension().expect("extension").to_str().expect("to_str") == "rs"
     }
 
@@ -0,0 +1,56 @@
+// Compiler:
+//   exec-arg: --emit=asm=-,link
+//   stdout:
+//     ...
+//     ...,1), %tmm2
+//     ...
+//     ...,1), %tmm3
+//     ...
+//     ...,1), %tmm1
+//     ...
+//     tdpbssd	%tmm3, %tmm2, %tmm1
+//     ...
+//     tilestored	%tmm1, (...
+//     ...
+//
+// Run-time:
+//   status: 0
+
+// Check that the tile numbers of the AMX intrinsics are encoded in the emitted instructions. The
+// instructions are not executed since they require the support of both the CPU and the kernel.
+
+#![feature(link_llvm_intrinsics)]
+#![allow(internal_features)]
+
+#[cfg(target_arch = "x86_64")]
+mod amx {
+    extern "C" {
+        #[link_name = "llvm.x86.tileloadd64"]
+        fn tileloadd64(dst: i8, base: *const u8, stride: usize);
+        #[link_name = "llvm.x86.tilestored64"]
+        fn tilestored64(dst: i8, base: *mut u8, stride: usize);
+        #[link_name = "llvm.x86.tdpbssd"]
+        fn tdpbssd(dst: i8, a: i8, b: i8);
+    }
+
+    #[inline(never)]
+    pub fn multiply_accumulate(a: &[u8; 1024], b: &[u8; 1024], c: &mut [u8; 1024]) {
+        unsafe {
+            tileloadd64(2, a.as_ptr(), 64);
+            tileloadd64(3, b.as_ptr(), 64);
+            tileloadd64(1, c.as_ptr(), 64);
+            tdpbssd(1, 2, 3);
+            tilestored64(1, c.as_mut_ptr(), 64);
+        }
+    }
+}
+
+fn main() {
+    #[cfg(target_arch = "x86_64")]
+    if std::hint::black_box(false) {
+        let a = [0; 1024];
+        let b = [0; 1024];
+        let mut c = [0; 1024];
+        amx::multiply_accumulate(&a, &b, &mut c);
+    }
+}
@@ -0,0 +1,20 @@
+// Compiler:
+//   status: error
+//   stderr:
+//     ...
+//     error: the tile arguments of the LLVM intrinsic `llvm.x86.tilezero` must be constants
+//     ...
+
+#![feature(link_llvm_intrinsics)]
+#![allow(internal_features)]
+
+extern "C" {
+    #[link_name = "llvm.x86.tilezero"]
+    fn tilezero(dst: i8);
+}
+
+fn main() {
+    if std::hint::black_box(false) {
+        unsafe { tilezero(std::hint::black_box(1)) };
+    }
+}
@@ -0,0 +1,81 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "riscv64"))]
+use std::arch::asm;
+
+#[cfg(target_arch = "x86_64")]
+fn explicit_registers() {
+    use std::arch::x86_64::*;
+
+    let x: u64;
+    unsafe {
+        asm!("lea {}, [rdi + rsi]", out(reg) x, in("rdi") 40u64, in("rsi") 2u64);
+    }
+    assert_eq!(x, 42);
+
+    let sum: f32;
+    unsafe {
+        asm!(
+            "addss xmm0, xmm1",
+            "movss {}, xmm0",
+            out(xmm_reg) sum,
+            inout("xmm0") 1.5f32 => _,
+            in("xmm1") 2.25f32,
+        );
+    }
+    assert_eq!(sum, 3.75);
+
+    let mut vector = unsafe { _mm_set_epi32(4, 3, 2, 1) };
+    unsafe {
+        asm!("paddd xmm2, xmm2", inout("xmm2") vector);
+    }
+    let mut lanes = [0i32; 4];
+    unsafe { _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, vector) };
+    assert_eq!(lanes, [2, 4, 6, 8]);
+}
+
+#[cfg(target_arch = "aarch64")]
+fn explicit_registers() {
+    let x: u64;
+    unsafe {
+        asm!("add {}, x0, x1", out(reg) x, in("x0") 40u64, in("x1") 2u64);
+    }
+    assert_eq!(x, 42);
+
+    let mut y: u64 = 21;
+    unsafe {
+        asm!("add x9, x9, x9", inout("x9") y);
+    }
+    assert_eq!(y, 42);
+
+    let sum: f64;
+    unsafe {
+        asm!("fadd d0, d0, d1", inout("v0") 1.5f64 => sum, in("v1") 2.25f64);
+    }
+    assert_eq!(sum, 3.75);
+}
+
+#[cfg(target_arch = "riscv64")]
+fn explicit_registers() {
+    let x: u64;
+    unsafe {
+        asm!("add {}, a0, a1", out(reg) x, in("a0") 40u64, in("a1") 2u64);
+    }
+    assert_eq!(x, 42);
+
+    let mut y: u64 = 21;
+    unsafe {
+        asm!("add t0, t0, t0", inout("t0") y);
+    }
+    assert_eq!(y, 42);
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "riscv64")))]
+fn explicit_registers() {}
+
+fn main() {
+    explicit_registers();
+}
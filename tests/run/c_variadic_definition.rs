@@ -0,0 +1,16 @@
+// Compiler:
+//   status: error
+//   stderr:
+//     ...
+//     error: GCC backend does not support defining C-variadic functions
+//     ...
+
+#![feature(c_variadic)]
+
+unsafe extern "C" fn sum(count: usize, mut args: ...) -> usize {
+    (0..count).map(|_| args.arg::<usize>()).sum()
+}
+
+fn main() {
+    println!("{}", unsafe { sum(3, 20usize, 20usize, 2usize) });
+}
@@ -0,0 +1,29 @@
+// Compiler:
+//   exec-arg: -Zcf-protection=full
+//
+// Run-time:
+//   status: 0
+
+#[cfg(target_arch = "x86_64")]
+fn check_landing_pads() {
+    use std::hint::black_box;
+
+    #[inline(never)]
+    extern "C" fn indirect_target(value: u32) -> u32 {
+        value * 2
+    }
+
+    let function = black_box(indirect_target as extern "C" fn(u32) -> u32);
+    assert_eq!(function(21), 42);
+
+    // NOTE: with indirect branch tracking, the functions start with an endbr64 instruction.
+    let code = unsafe { *(function as *const [u8; 4]) };
+    assert_eq!(code, [0xF3, 0x0F, 0x1E, 0xFA]);
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn check_landing_pads() {}
+
+fn main() {
+    check_landing_pads();
+}
@@ -0,0 +1,41 @@
+// Compiler:
+//   exec-arg: -Cforce-frame-pointers=yes
+//
+// Run-time:
+//   status: 0
+
+#[cfg(target_arch = "x86_64")]
+fn check_frame_pointers() {
+    use std::hint::black_box;
+
+    #[inline(never)]
+    extern "C" fn leaf(value: u32) -> u32 {
+        value.wrapping_mul(3)
+    }
+
+    #[inline(never)]
+    extern "C" fn non_leaf(value: u32) -> u32 {
+        black_box(leaf)(value) + 1
+    }
+
+    for function in [leaf as extern "C" fn(u32) -> u32, non_leaf] {
+        let function = black_box(function);
+        assert!(function(14) >= 42);
+
+        let code = unsafe { *(function as *const [u8; 8]) };
+        let mut code = code.as_slice();
+        // NOTE: skip the endbr64 instruction emitted when indirect branch tracking is enabled.
+        if code.starts_with(&[0xF3, 0x0F, 0x1E, 0xFA]) {
+            code = &code[4..];
+        }
+        // push rbp; mov rbp, rsp
+        assert_eq!(&code[..4], [0x55, 0x48, 0x89, 0xE5]);
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn check_frame_pointers() {}
+
+fn main() {
+    check_frame_pointers();
+}
@@ -0,0 +1,47 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#[cfg(target_os = "linux")]
+mod link_section {
+    use std::hint::black_box;
+
+    extern "C" {
+        static __start_rustc_gcc_functions: u8;
+        static __stop_rustc_gcc_functions: u8;
+    }
+
+    #[inline(never)]
+    #[link_section = "rustc_gcc_functions"]
+    pub extern "C" fn in_custom_section(value: u32) -> u32 {
+        value.wrapping_mul(3) + 3
+    }
+
+    #[inline(never)]
+    #[link_section = "rustc_gcc_functions"]
+    fn also_in_custom_section(value: u32) -> u32 {
+        black_box(in_custom_section)(value) - 3
+    }
+
+    pub fn check() {
+        let start = unsafe { &__start_rustc_gcc_functions as *const u8 as usize };
+        let end = unsafe { &__stop_rustc_gcc_functions as *const u8 as usize };
+
+        let functions = [
+            in_custom_section as extern "C" fn(u32) -> u32 as usize,
+            also_in_custom_section as fn(u32) -> u32 as usize,
+        ];
+        for function in functions {
+            assert!((start..end).contains(&black_box(function)));
+        }
+        assert!(!(start..end).contains(&(check as fn() as usize)));
+
+        assert_eq!(black_box(also_in_custom_section as fn(u32) -> u32)(14), 42);
+    }
+}
+
+fn main() {
+    #[cfg(target_os = "linux")]
+    link_section::check();
+}
@@ -0,0 +1,20 @@
+// Compiler:
+//   exec-arg: -Dwarnings
+//
+// Run-time:
+//   status: 0
+
+// Check that no warning is emitted for a function with a `#[link_section]` that was inlined in all
+// of its callers, since its section is never emitted.
+
+use std::hint::black_box;
+
+#[inline(always)]
+#[link_section = ".text.rustc_gcc_inlined"]
+fn inlined_in_custom_section(value: u32) -> u32 {
+    value.wrapping_mul(3) + 3
+}
+
+fn main() {
+    assert_eq!(inlined_in_custom_section(black_box(13)), 42);
+}
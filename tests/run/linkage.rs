@@ -0,0 +1,35 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(linkage)]
+
+use std::hint::black_box;
+
+extern "C" {
+    #[linkage = "extern_weak"]
+    static UNDEFINED_WEAK_SYMBOL: Option<extern "C" fn() -> u32>;
+
+    #[linkage = "extern_weak"]
+    fn undefined_weak_function() -> u32;
+}
+
+#[no_mangle]
+#[linkage = "weak"]
+pub static WEAK_STATIC: u32 = 40;
+
+#[no_mangle]
+#[linkage = "weak"]
+pub extern "C" fn weak_function() -> u32 {
+    2
+}
+
+fn main() {
+    // NOTE: an undefined extern weak symbol resolves to null instead of failing to link.
+    assert!(unsafe { UNDEFINED_WEAK_SYMBOL }.is_none());
+    assert!(black_box(undefined_weak_function as *const ()).is_null());
+
+    let function = black_box(weak_function as extern "C" fn() -> u32);
+    assert_eq!(black_box(&WEAK_STATIC) + function(), 42);
+}
@@ -0,0 +1,39 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(asm_const, naked_functions)]
+
+#[cfg(target_arch = "x86_64")]
+#[naked]
+extern "C" fn add(a: u64, b: u64) -> u64 {
+    unsafe {
+        std::arch::asm!("lea rax, [rdi + rsi]", "ret", options(noreturn));
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[naked]
+extern "C" fn add_const(a: u64) -> u64 {
+    unsafe {
+        std::arch::asm!("lea rax, [rdi + {}]", "ret", const 40, options(noreturn));
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[naked]
+extern "C" fn call_add(a: u64, b: u64) -> u64 {
+    unsafe {
+        std::arch::asm!("jmp {}", sym add, options(noreturn));
+    }
+}
+
+fn main() {
+    #[cfg(target_arch = "x86_64")]
+    {
+        assert_eq!(add(3, 4), 7);
+        assert_eq!(add_const(2), 42);
+        assert_eq!(call_add(10, 20), 30);
+    }
+}
@@ -0,0 +1,32 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#[cfg(target_arch = "aarch64")]
+fn main() {
+    use std::arch::aarch64::*;
+    use std::hint::black_box;
+
+    assert!(std::arch::is_aarch64_feature_detected!("neon"));
+
+    unsafe {
+        let saturated = vqaddq_u8(vdupq_n_u8(black_box(200)), vdupq_n_u8(black_box(100)));
+        assert_eq!(vgetq_lane_u8::<7>(saturated), 255);
+
+        let halved = vhadd_u8(vdup_n_u8(black_box(255)), vdup_n_u8(black_box(3)));
+        assert_eq!(vget_lane_u8::<0>(halved), 129);
+
+        let difference = vabd_s16(vdup_n_s16(black_box(-7)), vdup_n_s16(black_box(5)));
+        assert_eq!(vget_lane_s16::<3>(difference), 12);
+
+        // NOTE: the sum of the lanes wraps around since the result is an i8.
+        assert_eq!(vaddv_s8(vdup_n_s8(black_box(20))), -96);
+        assert_eq!(vaddvq_u16(vdupq_n_u16(black_box(1000))), 8000);
+        assert_eq!(vmaxvq_s32(vsetq_lane_s32::<2>(black_box(42), vdupq_n_s32(-1))), 42);
+        assert_eq!(vminvq_f32(vsetq_lane_f32::<1>(black_box(-2.5), vdupq_n_f32(1.0))), -2.5);
+    }
+}
+
+#[cfg(not(target_arch = "aarch64"))]
+fn main() {}
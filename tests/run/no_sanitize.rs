@@ -0,0 +1,18 @@
+// Compiler:
+//   exec-arg: -Zsanitizer=address
+//   status: error
+//   stderr:
+//     ...
+//     error: `#[no_sanitize]` is not supported by the GCC backend for the sanitizers: address
+//     ...
+
+#![feature(no_sanitize)]
+
+#[no_sanitize(address)]
+fn uninstrumented(values: &[u8]) -> u8 {
+    values[0]
+}
+
+fn main() {
+    println!("{}", uninstrumented(&[1, 2, 3]));
+}
@@ -0,0 +1,34 @@
+// Compiler:
+//   exec-arg: -Crelocation-model=pie
+//
+// Run-time:
+//   status: 0
+
+use std::hint::black_box;
+
+static VALUES: [u32; 4] = [1, 2, 3, 42];
+
+// NOTE: without position-independent code, the address of the static would be an absolute
+// relocation, which cannot be linked into a position-independent executable.
+#[inline(never)]
+fn value_address(index: usize) -> *const u32 {
+    &VALUES[index]
+}
+
+#[cfg(target_os = "linux")]
+fn check_executable_type() {
+    const ET_DYN: u16 = 3;
+
+    let header = std::fs::read("/proc/self/exe").unwrap();
+    assert_eq!(&header[..4], b"\x7FELF");
+    let executable_type = u16::from_ne_bytes([header[16], header[17]]);
+    assert_eq!(executable_type, ET_DYN);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_executable_type() {}
+
+fn main() {
+    assert_eq!(unsafe { *value_address(black_box(3)) }, 42);
+    check_executable_type();
+}
@@ -0,0 +1,32 @@
+// Compiler:
+//   exec-arg: -Ctarget-feature=+retpoline-indirect-branches,+retpoline-indirect-calls
+//
+// Run-time:
+//   status: 0
+
+#[cfg(target_arch = "x86_64")]
+fn check_thunks() {
+    use std::hint::black_box;
+
+    #[inline(never)]
+    fn indirect_target(value: u32) -> u32 {
+        value * 2
+    }
+
+    let function = black_box(indirect_target as fn(u32) -> u32);
+    assert_eq!(function(21), 42);
+
+    // NOTE: the indirect calls go through the thunks that GCC emits in the object file, so they
+    // must be in the symbol table of the executable. The name is built at run-time so that it is
+    // not found in the constants of this test.
+    let executable = std::fs::read("/proc/self/exe").expect("read executable");
+    let thunk = black_box("__x86_indirect-thunk_").replace('-', "_");
+    assert!(executable.windows(thunk.len()).any(|window| window == thunk.as_bytes()));
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn check_thunks() {}
+
+fn main() {
+    check_thunks();
+}
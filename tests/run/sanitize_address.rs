@@ -0,0 +1,19 @@
+// Compiler:
+//   exec-arg: -Zsanitizer=address
+//
+// Run-time:
+//   status: error
+//   stderr:
+//     ...
+//     ...AddressSanitizer: heap-buffer-overflow...
+//     ...
+
+use std::hint::black_box;
+
+fn main() {
+    let values = black_box(vec![1u8, 2, 3, 4]);
+    let pointer = black_box(values.as_ptr());
+    // NOTE: this reads one byte past the end of the allocation, so it must be reported.
+    let value = unsafe { std::ptr::read_volatile(pointer.add(4)) };
+    println!("{}", value);
+}
@@ -0,0 +1,66 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, portable_simd, repr_simd)]
+#![allow(internal_features, non_camel_case_types)]
+
+use std::hint::black_box;
+use std::intrinsics::simd::{simd_bitmask, simd_select_bitmask};
+use std::simd::{Mask, Simd};
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct i8x256([i8; 256]);
+
+fn lane_enabled(lane: usize) -> bool {
+    lane % 3 == 0 || lane % 7 == 1
+}
+
+fn check_mask<const N: usize>()
+where
+    std::simd::LaneCount<N>: std::simd::SupportedLaneCount,
+{
+    let mask = Mask::<i8, N>::from_array(black_box(std::array::from_fn(lane_enabled)));
+    let expected =
+        (0..N).filter(|&lane| lane_enabled(lane)).fold(0u64, |bits, lane| bits | 1 << lane);
+    assert_eq!(mask.to_bitmask(), expected);
+    assert_eq!(Mask::<i8, N>::from_bitmask(black_box(expected)), mask);
+
+    let values = Simd::<i8, N>::from_array(std::array::from_fn(|lane| lane as i8));
+    let others = Simd::<i8, N>::splat(-1);
+    let selected = mask.select(values, others);
+    for lane in 0..N {
+        let expected = if lane_enabled(lane) { lane as i8 } else { -1 };
+        assert_eq!(selected[lane], expected);
+    }
+}
+
+fn main() {
+    check_mask::<32>();
+    check_mask::<64>();
+
+    // NOTE: the bitmask of 256 lanes doesn't fit in an integer, so it is an array of bytes.
+    let lanes: [i8; 256] = black_box(std::array::from_fn(|lane| -(lane_enabled(lane) as i8)));
+    let mut expected = [0u8; 32];
+    for lane in 0..256 {
+        if lane_enabled(lane) {
+            if cfg!(target_endian = "little") {
+                expected[lane / 8] |= 1 << (lane % 8);
+            } else {
+                expected[lane / 8] |= 1 << (7 - lane % 8);
+            }
+        }
+    }
+    let bitmask: [u8; 32] = unsafe { simd_bitmask(i8x256(lanes)) };
+    assert_eq!(bitmask, expected);
+
+    let values = i8x256(std::array::from_fn(|lane| lane as i8));
+    let others = i8x256([-1; 256]);
+    let selected: i8x256 = unsafe { simd_select_bitmask(black_box(expected), values, others) };
+    for lane in 0..256 {
+        let expected = if lane_enabled(lane) { lane as i8 } else { -1 };
+        assert_eq!(selected.0[lane], expected);
+    }
+}
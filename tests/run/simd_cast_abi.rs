@@ -0,0 +1,64 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+// Check that a SIMD vector wrapped in a struct round trips through `extern "C"` functions: on
+// x86-64, such a struct is passed and returned with a cast to a vector register.
+
+#![feature(repr_simd)]
+#![allow(improper_ctypes_definitions, non_camel_case_types)]
+
+use std::hint::black_box;
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f32x4([f32; 4]);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i16x8([i16; 8]);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Floats {
+    values: f32x4,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Shorts {
+    values: i16x8,
+}
+
+#[inline(never)]
+extern "C" fn reverse_floats(floats: Floats) -> Floats {
+    let [a, b, c, d] = floats.values.0;
+    Floats { values: f32x4([d, c, b, a]) }
+}
+
+#[inline(never)]
+extern "C" fn negate_shorts(shorts: Shorts) -> Shorts {
+    Shorts { values: i16x8(shorts.values.0.map(|value| -value)) }
+}
+
+#[inline(never)]
+extern "C" fn identity_floats(floats: Floats) -> Floats {
+    floats
+}
+
+fn main() {
+    let floats = Floats { values: f32x4([1.5, -2.0, 3.25, 4.0]) };
+    let reverse_floats = black_box(reverse_floats as extern "C" fn(Floats) -> Floats);
+    assert_eq!(reverse_floats(black_box(floats)).values, f32x4([4.0, 3.25, -2.0, 1.5]));
+
+    let identity_floats = black_box(identity_floats as extern "C" fn(Floats) -> Floats);
+    assert_eq!(identity_floats(black_box(floats)), floats);
+
+    let shorts = Shorts { values: i16x8([1, -2, 3, -4, 5, -6, 7, i16::MAX]) };
+    let negate_shorts = black_box(negate_shorts as extern "C" fn(Shorts) -> Shorts);
+    assert_eq!(
+        negate_shorts(black_box(shorts)).values,
+        i16x8([-1, 2, -3, 4, -5, 6, -7, -i16::MAX])
+    );
+}
@@ -0,0 +1,48 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, repr_simd)]
+#![allow(internal_features, non_camel_case_types)]
+
+use std::hint::black_box;
+use std::intrinsics::simd::{
+    simd_ceil, simd_fabs, simd_floor, simd_fma, simd_fsqrt, simd_round, simd_trunc,
+};
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct f32x4([f32; 4]);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct f64x2([f64; 2]);
+
+fn main() {
+    let a = black_box(f32x4([-2.5, -0.0, 1.25, 9.75]));
+    let b = black_box(f64x2([-7.5, 16.5]));
+    unsafe {
+        let fabs: f32x4 = simd_fabs(a);
+        assert_eq!(fabs.0.map(f32::to_bits), a.0.map(|x| x.abs().to_bits()));
+        assert_eq!(simd_fabs(b).0, b.0.map(f64::abs));
+
+        let squares = f32x4([4.0, 0.25, 2.25, 81.0]);
+        assert_eq!(simd_fsqrt(squares).0, [2.0, 0.5, 1.5, 9.0]);
+        assert_eq!(simd_fsqrt(f64x2([6.25, 1e10])).0, [2.5, 1e5]);
+
+        assert_eq!(simd_floor(a).0, a.0.map(f32::floor));
+        assert_eq!(simd_ceil(a).0, a.0.map(f32::ceil));
+        assert_eq!(simd_trunc(a).0, a.0.map(f32::trunc));
+        assert_eq!(simd_round(a).0, a.0.map(f32::round));
+        assert_eq!(simd_floor(b).0, b.0.map(f64::floor));
+        assert_eq!(simd_ceil(b).0, b.0.map(f64::ceil));
+        assert_eq!(simd_trunc(b).0, b.0.map(f64::trunc));
+        assert_eq!(simd_round(b).0, b.0.map(f64::round));
+
+        let c = black_box(f32x4([0.5, 2.0, -1.0, 3.0]));
+        let fma: f32x4 = simd_fma(a, c, a);
+        let expected: Vec<f32> = (0..4).map(|i| a.0[i].mul_add(c.0[i], a.0[i])).collect();
+        assert_eq!(fma.0.to_vec(), expected);
+    }
+}
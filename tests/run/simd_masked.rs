@@ -0,0 +1,91 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, repr_simd)]
+#![allow(internal_features, non_camel_case_types)]
+
+use std::hint::black_box;
+use std::intrinsics::simd::{simd_masked_load, simd_masked_store};
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i32x4([i32; 4]);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f64x4([f64; 4]);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct m32x4([i32; 4]);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct m64x4([i64; 4]);
+
+fn load_reference<T: Copy>(mask: [bool; 4], memory: &[T], values: [T; 4]) -> [T; 4] {
+    let mut result = values;
+    for i in 0..4 {
+        if mask[i] {
+            result[i] = memory[i];
+        }
+    }
+    result
+}
+
+fn store_reference<T: Copy>(mask: [bool; 4], memory: &mut [T], values: [T; 4]) {
+    for i in 0..4 {
+        if mask[i] {
+            memory[i] = values[i];
+        }
+    }
+}
+
+fn main() {
+    let mask = black_box([true, false, true, false]);
+    let lanes = mask.map(|enabled| if enabled { -1 } else { 0 });
+
+    // NOTE: only the first two lanes are in bounds, so the disabled lanes must not be accessed.
+    let memory = black_box([1, 2, 3]);
+    let tail = &memory[1..];
+    let tail_mask = black_box([true, true, false, false]);
+    let tail_lanes = tail_mask.map(|enabled| if enabled { -1 } else { 0 });
+    let loaded: i32x4 =
+        unsafe { simd_masked_load(m32x4(tail_lanes), tail.as_ptr(), i32x4([10, 20, 30, 40])) };
+    assert_eq!(loaded, i32x4([2, 3, 30, 40]));
+
+    let ints = black_box([5, 6, 7, 8]);
+    let loaded: i32x4 =
+        unsafe { simd_masked_load(m32x4(lanes), ints.as_ptr(), i32x4([-1, -2, -3, -4])) };
+    assert_eq!(loaded.0, load_reference(mask, &ints, [-1, -2, -3, -4]));
+
+    let floats = black_box([0.5, 1.5, 2.5, 3.5]);
+    let float_lanes = mask.map(|enabled| if enabled { -1 } else { 0 });
+    let loaded: f64x4 = unsafe {
+        simd_masked_load(m64x4(float_lanes), floats.as_ptr(), f64x4([9.0, 9.0, 9.0, 9.0]))
+    };
+    assert_eq!(loaded.0, load_reference(mask, &floats, [9.0; 4]));
+
+    let mut stored = [0; 4];
+    let mut expected = [0; 4];
+    unsafe { simd_masked_store(m32x4(lanes), stored.as_mut_ptr(), i32x4([1, 2, 3, 4])) };
+    store_reference(mask, &mut expected, [1, 2, 3, 4]);
+    assert_eq!(stored, expected);
+
+    let mut stored = [0.0; 4];
+    let mut expected = [0.0; 4];
+    unsafe {
+        simd_masked_store(m64x4(float_lanes), stored.as_mut_ptr(), f64x4([1.0, 2.0, 3.0, 4.0]))
+    };
+    store_reference(mask, &mut expected, [1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(stored, expected);
+
+    // NOTE: the disabled lanes are past the end of the slice.
+    let mut short = [0; 3];
+    unsafe {
+        simd_masked_store(m32x4(tail_lanes), short[1..].as_mut_ptr(), i32x4([7, 8, 9, 10]))
+    };
+    assert_eq!(short, [0, 7, 8]);
+}
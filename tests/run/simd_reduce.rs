@@ -0,0 +1,86 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, repr_simd)]
+#![allow(internal_features, non_camel_case_types)]
+
+use std::hint::black_box;
+use std::intrinsics::simd::{
+    simd_reduce_add_ordered, simd_reduce_add_unordered, simd_reduce_max, simd_reduce_min,
+    simd_reduce_mul_ordered, simd_reduce_mul_unordered,
+};
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct f32x4([f32; 4]);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct i32x4([i32; 4]);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct f32x3([f32; 3]);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct i32x3([i32; 3]);
+
+fn main() {
+    let floats = black_box(f32x4([1.0, 2.0, 3.0, 4.0]));
+    let ints = black_box(i32x4([7, -3, 12, 5]));
+    unsafe {
+        let add_ordered: f32 = simd_reduce_add_ordered(floats, 0.5);
+        let add_unordered: f32 = simd_reduce_add_unordered(floats);
+        let mul_ordered: f32 = simd_reduce_mul_ordered(floats, 2.0);
+        let mul_unordered: f32 = simd_reduce_mul_unordered(floats);
+        let min: f32 = simd_reduce_min(floats);
+        let max: f32 = simd_reduce_max(floats);
+        assert_eq!(add_ordered, 10.5);
+        assert_eq!(add_unordered, 10.0);
+        assert_eq!(mul_ordered, 48.0);
+        assert_eq!(mul_unordered, 24.0);
+        assert_eq!(min, 1.0);
+        assert_eq!(max, 4.0);
+
+        let add_ordered: i32 = simd_reduce_add_ordered(ints, 1);
+        let add_unordered: i32 = simd_reduce_add_unordered(ints);
+        let mul_unordered: i32 = simd_reduce_mul_unordered(ints);
+        let min: i32 = simd_reduce_min(ints);
+        let max: i32 = simd_reduce_max(ints);
+        assert_eq!(add_ordered, 22);
+        assert_eq!(add_unordered, 21);
+        assert_eq!(mul_unordered, -1260);
+        assert_eq!(min, -3);
+        assert_eq!(max, 12);
+
+        // NOTE: the identity of the floating-point addition is -0.0, not 0.0.
+        let negative_zeros = black_box(f32x4([-0.0; 4]));
+        let add_unordered: f32 = simd_reduce_add_unordered(negative_zeros);
+        assert_eq!(add_unordered.to_bits(), (-0.0f32).to_bits());
+
+        let floats = black_box(f32x3([1.0, 2.0, 4.0]));
+        let ints = black_box(i32x3([7, -3, 12]));
+        let add_ordered: f32 = simd_reduce_add_ordered(floats, 0.5);
+        let add_unordered: f32 = simd_reduce_add_unordered(floats);
+        let mul_unordered: f32 = simd_reduce_mul_unordered(floats);
+        let min: f32 = simd_reduce_min(floats);
+        let max: f32 = simd_reduce_max(floats);
+        assert_eq!(add_ordered, 7.5);
+        assert_eq!(add_unordered, 7.0);
+        assert_eq!(mul_unordered, 8.0);
+        assert_eq!(min, 1.0);
+        assert_eq!(max, 4.0);
+
+        let add_unordered: i32 = simd_reduce_add_unordered(ints);
+        let mul_unordered: i32 = simd_reduce_mul_unordered(ints);
+        let min: i32 = simd_reduce_min(ints);
+        let max: i32 = simd_reduce_max(ints);
+        assert_eq!(add_unordered, 16);
+        assert_eq!(mul_unordered, -252);
+        assert_eq!(min, -3);
+        assert_eq!(max, 12);
+    }
+}
@@ -0,0 +1,39 @@
+// Compiler:
+//   exec-arg: -Ctarget-feature=+soft-float
+//
+// Run-time:
+//   status: 0
+
+use std::hint::black_box;
+
+// NOTE: the floating-point values are only compared through their bits so that no floating-point
+// value is passed to the standard library, which uses the hard-float ABI.
+
+#[inline(never)]
+fn scale(value: f32, factor: f64) -> f64 {
+    value as f64 * factor
+}
+
+#[inline(never)]
+fn swap(pair: (f32, f32)) -> (f32, f32) {
+    (pair.1, pair.0)
+}
+
+#[inline(never)]
+fn halve(value: f64) -> (f64, u32) {
+    (value / 2.0, value as u32)
+}
+
+fn main() {
+    let scaled = black_box(scale as fn(f32, f64) -> f64)(1.5, 4.0);
+    assert_eq!(scaled.to_bits(), 6.0f64.to_bits());
+    assert_eq!(scale(black_box(-0.5), 3.0).to_bits(), (-1.5f64).to_bits());
+
+    let (first, second) = black_box(swap as fn((f32, f32)) -> (f32, f32))((1.25, -2.5));
+    assert_eq!(first.to_bits(), (-2.5f32).to_bits());
+    assert_eq!(second.to_bits(), 1.25f32.to_bits());
+
+    let (half, integer) = halve(black_box(9.0));
+    assert_eq!(half.to_bits(), 4.5f64.to_bits());
+    assert_eq!(integer, 9);
+}
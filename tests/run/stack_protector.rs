@@ -0,0 +1,25 @@
+// Compiler:
+//   exec-arg: -Zstack-protector=all
+//
+// Run-time:
+//   status: signal
+//   stderr:
+//     ...
+//     ...stack smashing detected...
+//     ...
+
+use std::hint::black_box;
+
+#[inline(never)]
+fn overflow(len: usize) {
+    let mut buffer = [0u8; 16];
+    let pointer = black_box(buffer.as_mut_ptr());
+    // NOTE: this overwrites the canary placed after the buffer, which must be detected before
+    // returning.
+    unsafe { std::ptr::write_bytes(pointer, 0xFF, len) };
+    black_box(&buffer);
+}
+
+fn main() {
+    overflow(black_box(64));
+}
@@ -0,0 +1,33 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+//   stdout: 10
+//      hello world
+
+#![feature(unsized_locals, unsized_fn_params)]
+#![allow(incomplete_features)]
+
+fn sum(values: [u32]) -> u32 {
+    let mut sum = 0;
+    for value in values.iter() {
+        sum += value;
+    }
+    sum
+}
+
+fn call(function: dyn FnOnce() -> String) -> String {
+    function()
+}
+
+fn main() {
+    let values: Box<[u32]> = vec![1, 2, 3, 4].into_boxed_slice();
+    // NOTE: moving out of the box into an unsized local requires a dynamic alloca.
+    let values: [u32] = *values;
+    println!("{}", sum(values));
+
+    let world = String::from("world");
+    let function: Box<dyn FnOnce() -> String> = Box::new(move || format!("hello {}", world));
+    let function: dyn FnOnce() -> String = *function;
+    println!("{}", call(function));
+}
@@ -0,0 +1,20 @@
+// ignore-if: test "$(uname -m)" != x86_64
+// Compiler:
+//   status: error
+//   stderr:
+//     ...
+//     error: the calling convention `X86_64Win64` is not supported by the GCC backend
+//     ...
+
+extern "win64" fn win64_function(value: u32) -> u32 {
+    value * 2
+}
+
+// NOTE: a function pointer type with this calling convention is not an error by itself.
+fn call(function: extern "win64" fn(u32) -> u32) -> u32 {
+    function(21)
+}
+
+fn main() {
+    println!("{}", call(win64_function));
+}
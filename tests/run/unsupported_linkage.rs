@@ -0,0 +1,17 @@
+// Compiler:
+//   status: error
+//   stderr:
+//     ...
+//     error: GCC backend does not support the linkage `Appending`
+//     ...
+
+#![feature(linkage)]
+
+extern "C" {
+    #[linkage = "appending"]
+    static APPENDING_SYMBOL: *const u8;
+}
+
+fn main() {
+    println!("{:?}", unsafe { APPENDING_SYMBOL });
+}
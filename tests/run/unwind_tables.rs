@@ -0,0 +1,92 @@
+// Compiler:
+//   exec-arg: -Cpanic=abort
+//   exec-arg: -Cforce-unwind-tables=yes
+//
+// Run-time:
+//   status: 0
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+mod eh_frame_hdr {
+    #[repr(C)]
+    struct ProgramHeader {
+        kind: u32,
+        flags: u32,
+        offset: u64,
+        virtual_address: u64,
+        physical_address: u64,
+        file_size: u64,
+        memory_size: u64,
+        align: u64,
+    }
+
+    #[repr(C)]
+    struct DlPhdrInfo {
+        address: usize,
+        name: *const u8,
+        program_headers: *const ProgramHeader,
+        program_header_count: u16,
+    }
+
+    extern "C" {
+        fn dl_iterate_phdr(
+            callback: extern "C" fn(*const DlPhdrInfo, usize, *mut usize) -> i32,
+            data: *mut usize,
+        ) -> i32;
+    }
+
+    const PT_GNU_EH_FRAME: u32 = 0x6474E550;
+
+    extern "C" fn find_eh_frame_hdr(
+        info: *const DlPhdrInfo,
+        _size: usize,
+        data: *mut usize,
+    ) -> i32 {
+        let info = unsafe { &*info };
+        for index in 0..info.program_header_count as usize {
+            let header = unsafe { &*info.program_headers.add(index) };
+            if header.kind == PT_GNU_EH_FRAME {
+                unsafe { *data = info.address + header.virtual_address as usize };
+            }
+        }
+        // NOTE: the executable is the first object, so there's no need to look at the others.
+        1
+    }
+
+    /// Returns whether the search table of the executable has an unwind entry for this address.
+    pub fn has_unwind_entry(address: usize) -> bool {
+        let mut hdr = 0;
+        unsafe { dl_iterate_phdr(find_eh_frame_hdr, &mut hdr) };
+        assert_ne!(hdr, 0, "missing .eh_frame_hdr");
+
+        let hdr = hdr as *const u8;
+        let encodings = unsafe { *(hdr as *const [u8; 4]) };
+        // NOTE: version 1, eh_frame_ptr as pcrel sdata4, fde_count as udata4 and the table as
+        // datarel sdata4 entries, which is what the linkers emit.
+        assert_eq!(encodings, [1, 0x1B, 0x03, 0x3B]);
+        let fde_count = unsafe { (hdr.add(8) as *const u32).read_unaligned() } as usize;
+        let table = unsafe { hdr.add(12) as *const [i32; 2] };
+        (0..fde_count).any(|index| {
+            let [location, _fde] = unsafe { table.add(index).read_unaligned() };
+            (hdr as isize + location as isize) as usize == address
+        })
+    }
+}
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+fn check_unwind_tables() {
+    #[inline(never)]
+    extern "C" fn leaf(value: u32) -> u32 {
+        value.wrapping_mul(3)
+    }
+
+    let function = std::hint::black_box(leaf as extern "C" fn(u32) -> u32);
+    assert_eq!(function(14), 42);
+    assert!(eh_frame_hdr::has_unwind_entry(function as usize));
+}
+
+#[cfg(not(all(target_os = "linux", target_pointer_width = "64")))]
+fn check_unwind_tables() {}
+
+fn main() {
+    check_unwind_tables();
+}
@@ -0,0 +1,35 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(used_with_arg)]
+
+use std::hint::black_box;
+
+const KEY: u8 = 0x5a;
+
+// NOTE: this static is never referenced, so only `#[used(linker)]` keeps its section when
+// linking with --gc-sections (which rustc does by default). The section name is not a C identifier
+// so that the linker does not keep it for the `__start_`/`__stop_` symbols.
+#[used(linker)]
+#[link_section = ".rustc_gcc_used_linker"]
+static USED_LINKER: [u8; 16] = *b"gcc-used(linker)";
+
+fn main() {
+    // NOTE: the expected bytes are computed at runtime so that they don't appear in the binary.
+    let key = black_box(KEY);
+    let expected: Vec<u8> = [
+        b'g' ^ KEY, b'c' ^ KEY, b'c' ^ KEY, b'-' ^ KEY, b'u' ^ KEY, b's' ^ KEY, b'e' ^ KEY,
+        b'd' ^ KEY, b'(' ^ KEY, b'l' ^ KEY, b'i' ^ KEY, b'n' ^ KEY, b'k' ^ KEY, b'e' ^ KEY,
+        b'r' ^ KEY, b')' ^ KEY,
+    ]
+    .iter()
+    .map(|byte| byte ^ key)
+    .collect();
+
+    let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    if !exe.windows(expected.len()).any(|window| window == expected) {
+        std::process::exit(1);
+    }
+}